@@ -0,0 +1,75 @@
+//! Counts the allocations and measures the time of deserializing large tables into a
+//! `HashMap<String, _>`, whose keys should only be copied once, by the map receiving them.
+//!
+//! Run with `cargo bench --bench map_keys`.
+
+extern crate config;
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+
+use config::*;
+
+const ITERATIONS: u32 = 100;
+
+/// Counts the allocations of the whole process, the benchmark only uses one thread.
+struct Counting;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+fn allocations<T, F: FnOnce() -> T>(f: F) -> (T, usize) {
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    let result = f();
+    (result, ALLOCATIONS.load(Ordering::Relaxed) - before)
+}
+
+fn large_table(len: usize) -> Value {
+    let table: Map<String, Value> = (0..len)
+        .map(|i| (format!("key{}", i), Value::from(i as i64)))
+        .collect();
+    table.into()
+}
+
+fn main() {
+    for &len in &[100, 1_000, 10_000] {
+        let value = large_table(len);
+
+        // Deserializing consumes the value, leave out the allocations of cloning it
+        let (_, cloning) = allocations(|| value.clone());
+        let (map, total) =
+            allocations(|| value.clone().try_into::<HashMap<String, i64>>().unwrap());
+        assert_eq!(map.len(), len);
+        let deserializing = total - cloning;
+
+        let start = Instant::now();
+        for _ in 0..ITERATIONS {
+            let map: HashMap<String, i64> = value.clone().try_into().unwrap();
+            assert_eq!(map.len(), len);
+        }
+        let elapsed = start.elapsed() / ITERATIONS;
+
+        println!(
+            "{:>6} keys: {:>6} allocations ({:.2} per key), {:>12?} per iteration",
+            len,
+            deserializing,
+            deserializing as f64 / len as f64,
+            elapsed
+        );
+    }
+}
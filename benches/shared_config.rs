@@ -0,0 +1,60 @@
+//! Compares the cost of cloning a large [`Config`] with that of a [`SharedConfig`].
+//!
+//! Run with `cargo bench --bench shared_config`.
+
+extern crate config;
+
+use std::time::Instant;
+
+use config::*;
+
+const ITERATIONS: u32 = 1000;
+
+/// Hides `value` from the optimizer, so that the work producing it is not removed.
+fn black_box<T>(value: T) -> T {
+    unsafe {
+        let copy = std::ptr::read_volatile(&value);
+        std::mem::forget(value);
+        copy
+    }
+}
+
+fn bench<T, F: FnMut() -> T>(name: &str, mut f: F) {
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        black_box(f());
+    }
+
+    println!(
+        "{:<24} {:>12?} per iteration",
+        name,
+        start.elapsed() / ITERATIONS
+    );
+}
+
+/// A configuration of 100 tables of 100 keys each.
+fn large_config() -> Config {
+    let mut builder = Config::builder();
+    for section in 0..100 {
+        for key in 0..100 {
+            let path = format!("section{}.key{}", section, key);
+            builder = builder.set_default(path, key).unwrap();
+        }
+    }
+
+    builder.build().unwrap()
+}
+
+fn main() {
+    let config = large_config();
+    let shared = config.clone().into_shared();
+
+    bench("clone Config", || config.clone());
+    bench("clone SharedConfig", || shared.clone());
+    bench("get from Config", || {
+        config.get::<i64>("section50.key50").unwrap()
+    });
+    bench("get from SharedConfig", || {
+        shared.get::<i64>("section50.key50").unwrap()
+    });
+}
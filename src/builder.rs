@@ -1,9 +1,22 @@
+use std::env;
+use std::fmt;
 use std::iter::IntoIterator;
+use std::path::Path;
 use std::str::FromStr;
+use std::sync::{Arc, Mutex};
 
-use crate::error::Result;
+use serde::de::DeserializeOwned;
+use serde::ser::Serialize;
+
+use crate::case::KeyCase;
+use crate::de::{BoolTokens, DeOptions};
+use crate::error::{ConfigError, Result};
+use crate::explain::ConfigExplanation;
+use crate::file::{File, FileFormat, FileSet};
 use crate::map::Map;
+use crate::placeholder;
 use crate::source::AsyncSource;
+use crate::value::{FloatPolicy, ValueKind};
 use crate::{config::Config, path::Expression, source::Source, value::Value};
 
 /// A configuration builder
@@ -90,6 +103,11 @@ use crate::{config::Config, path::Expression, source::Source, value::Value};
 pub struct ConfigBuilder<St: BuilderState> {
     defaults: Map<Expression, Value>,
     overrides: Map<Expression, Value>,
+    merge_options: MergeOptions,
+    on_override: Option<OverrideHook>,
+    map_transforms: Vec<MapTransform>,
+    de_options: DeOptions,
+    key_separator: Option<char>,
     state: St,
 }
 
@@ -100,6 +118,29 @@ pub trait BuilderState {}
 #[derive(Debug, Default)]
 pub struct DefaultState {
     sources: Vec<Box<dyn Source + Send + Sync>>,
+    names: Map<String, usize>,
+
+    /// Tier of each of `sources`, which are kept sorted by it
+    tiers: Vec<Tier>,
+}
+
+/// Precedence tier of a [`Source`] registered with [`ConfigBuilder::add_source_to_tier`].
+///
+/// Sources of a higher tier take precedence over those of a lower one whatever the order they
+/// were added in, from `File` to `Override`; the ones of a same tier take precedence in the
+/// order they were added. Sources added with [`add_source`](ConfigBuilder::add_source) are in
+/// the `File` tier. Overrides set with [`set_override`](ConfigBuilder::set_override) are still
+/// above all the tiers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Tier {
+    /// Files and other sources added with `add_source`
+    File,
+
+    /// Environment variables, above files
+    Env,
+
+    /// Sources overriding everything else
+    Override,
 }
 
 /// The asynchronous configuration builder.
@@ -141,6 +182,71 @@ enum SourceType {
 impl BuilderState for DefaultState {}
 impl BuilderState for AsyncState {}
 
+type OverrideFn = dyn Fn(&str, &Value, &Value) + Send + Sync;
+
+/// Callback registered with [`ConfigBuilder::on_override`].
+#[derive(Clone)]
+pub(crate) struct OverrideHook(Arc<OverrideFn>);
+
+impl OverrideHook {
+    /// Reports every value in `cache` that setting `value` at `expr` is about to replace.
+    fn report(&self, cache: &Value, expr: &Expression, value: &Value) {
+        match value.kind {
+            // Tables are merged deeply, so only their leaves can shadow something
+            ValueKind::Table(ref table) => {
+                for (key, val) in table {
+                    let child = Expression::Child(Box::new(expr.clone()), key.clone());
+                    self.report(cache, &child, val);
+                }
+            }
+
+            _ => {
+                if let Some(existing) = expr.clone().get(cache) {
+                    (self.0)(&expr.to_string(), existing, value);
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Debug for OverrideHook {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "OverrideHook")
+    }
+}
+
+type MapTransformFn = dyn Fn(Map<String, Value>) -> Result<Map<String, Value>> + Send + Sync;
+
+/// Transform registered with [`ConfigBuilder::map_transform`].
+#[derive(Clone)]
+pub(crate) struct MapTransform(Arc<MapTransformFn>);
+
+impl fmt::Debug for MapTransform {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "MapTransform")
+    }
+}
+
+/// Passes the merged configuration through every transform, in the order they were registered.
+fn apply_transforms(mut config: Config, transforms: &[MapTransform]) -> Result<Config> {
+    config.cache = transform_cache(config.cache, transforms)?;
+    Ok(config.with_transforms(transforms.to_vec()))
+}
+
+/// Runs `transforms` in order over the merged configuration `cache`.
+pub(crate) fn transform_cache(cache: Value, transforms: &[MapTransform]) -> Result<Value> {
+    if transforms.is_empty() {
+        return Ok(cache);
+    }
+
+    let mut map = cache.into_table()?;
+    for transform in transforms {
+        map = (transform.0)(map)?;
+    }
+
+    Ok(Value::new(None, map))
+}
+
 impl<St: BuilderState> ConfigBuilder<St> {
     // operations allowed in any state
 
@@ -150,14 +256,32 @@ impl<St: BuilderState> ConfigBuilder<St> {
     ///
     /// # Errors
     ///
-    /// Fails if `Expression::from_str(key)` fails.
+    /// Fails if `key` is not a valid path.
     pub fn set_default<S, T>(mut self, key: S, value: T) -> Result<ConfigBuilder<St>>
     where
         S: AsRef<str>,
         T: Into<Value>,
     {
-        self.defaults
-            .insert(Expression::from_str(key.as_ref())?, value.into());
+        let key = Expression::parse_with_separator(key.as_ref(), self.separator())?;
+        self.defaults.insert(key, value.into());
+        Ok(self)
+    }
+
+    /// Set defaults from the fields of `value`, such as a struct implementing `Default`.
+    ///
+    /// `value` is serialized and each of its top-level fields becomes a default at its key,
+    /// replacing a default previously set at that same key.
+    ///
+    /// # Errors
+    ///
+    /// Fails if `value` cannot be serialized or does not serialize to a table.
+    pub fn set_defaults_from<T>(mut self, value: &T) -> Result<ConfigBuilder<St>>
+    where
+        T: Serialize,
+    {
+        for (key, value) in Value::try_from(value)?.into_table()? {
+            self.defaults.insert(Expression::Identifier(key), value);
+        }
         Ok(self)
     }
 
@@ -167,16 +291,219 @@ impl<St: BuilderState> ConfigBuilder<St> {
     ///
     /// # Errors
     ///
-    /// Fails if `Expression::from_str(key)` fails.
+    /// Fails if `key` is not a valid path.
     pub fn set_override<S, T>(mut self, key: S, value: T) -> Result<ConfigBuilder<St>>
     where
         S: AsRef<str>,
         T: Into<Value>,
     {
-        self.overrides
-            .insert(Expression::from_str(key.as_ref())?, value.into());
+        let key = Expression::parse_with_separator(key.as_ref(), self.separator())?;
+        self.overrides.insert(key, value.into());
         Ok(self)
     }
+
+    /// Decode strings from base64 when they are deserialized as bytes.
+    ///
+    /// This applies to targets deserializing from bytes, such as `serde_bytes::ByteBuf` or
+    /// fields with `#[serde(with = "serde_bytes")]`, and fails on invalid base64. By default
+    /// such targets receive the UTF-8 bytes of the string. A plain `Vec<u8>` deserializes from
+    /// an array of integers either way.
+    pub fn bytes_as_base64(mut self, enabled: bool) -> Self {
+        self.de_options.bytes_as_base64 = enabled;
+        self
+    }
+
+    /// Set how non-finite floats are treated when deserializing, see [`FloatPolicy`].
+    ///
+    /// With [`FloatPolicy::Reject`], a non-finite float deserializes as a string, e.g. `"inf"`,
+    /// and deserializing it as a float fails.
+    pub fn float_policy(mut self, policy: FloatPolicy) -> Self {
+        self.de_options.float_policy = policy;
+        self
+    }
+
+    /// Accept `true_tokens` and `false_tokens`, compared ignoring case, as the only strings
+    /// deserializing to a `bool`, instead of `true`, `on`, `yes`, `1` and their opposites.
+    ///
+    /// This applies whenever a string is deserialized into a `bool`, e.g. with `y` and `n`
+    /// for a field `enabled: bool`; any other string then fails to deserialize. Values that
+    /// are already booleans or numbers are unaffected, as is [`Value::into_bool`].
+    pub fn bool_tokens(mut self, true_tokens: &[&str], false_tokens: &[&str]) -> Self {
+        let lowercase = |tokens: &[&str]| tokens.iter().map(|token| token.to_lowercase()).collect();
+
+        self.de_options.bool_tokens = Some(Arc::new(BoolTokens {
+            truthy: lowercase(true_tokens),
+            falsy: lowercase(false_tokens),
+        }));
+        self
+    }
+
+    /// Match the names of enum variants ignoring case when deserializing, so that `"FAST"`
+    /// deserializes to a variant `Fast`.
+    ///
+    /// By default, as in serde, the name must match exactly. A variant whose name matches
+    /// exactly is always preferred.
+    pub fn enum_case_insensitive(mut self, enabled: bool) -> Self {
+        self.de_options.enum_case_insensitive = enabled;
+        self
+    }
+
+    /// Merge defaults leaf by leaf, beneath everything set by sources and overrides.
+    ///
+    /// Tables are always merged deeply, so a default `server.host` survives a file that only
+    /// sets `server.port` either way. With deep defaults, defaults are applied last and fill
+    /// every leaf that is still absent or nil, so an explicit nil in a source no longer hides
+    /// a default. The [`on_override`](Self::on_override) hook is not called for defaults then.
+    pub fn deep_defaults(mut self, enabled: bool) -> Self {
+        self.merge_options.deep_defaults = enabled;
+        self
+    }
+
+    /// Fail to build if two sources provide values of different types for the same key, such
+    /// as `debug = true` in a file and `debug = "yes"` in another.
+    ///
+    /// The error names the key along with both types and origins. Sources overriding a value
+    /// with one of the same type are fine, and nil values as well as defaults and overrides
+    /// are not checked.
+    pub fn strict_types(mut self, enabled: bool) -> Self {
+        self.merge_options.strict_types = enabled;
+        self
+    }
+
+    /// Merge the arrays of tables at `path` element by element across sources, matching them
+    /// on their `key_field`, instead of replacing the array of a source with the next one.
+    ///
+    /// An element of a source whose `key_field` equals that of an element from a previous
+    /// layer, such as two `[[server]]` entries with the same `name`, is deep merged into it,
+    /// and the others are appended in order. Elements of the same source are not matched with
+    /// each other, so duplicates within a source are all kept. Elements without `key_field`,
+    /// or with a nil one, are always appended.
+    ///
+    /// The array of the first source setting `path` is merged into a default array the same
+    /// way, unless with [`deep_defaults`](Self::deep_defaults), where a default array is only
+    /// used if no source sets one. An array set by overrides replaces the merged one.
+    ///
+    /// # Errors
+    ///
+    /// Fails if `path` is not a valid path.
+    pub fn array_merge_by_key<S>(mut self, path: S, key_field: &str) -> Result<Self>
+    where
+        S: AsRef<str>,
+    {
+        let path = Expression::parse_with_separator(path.as_ref(), self.separator())?;
+        self.merge_options.array_keys.push(ArrayMergeKey {
+            path,
+            field: key_field.to_string(),
+        });
+        Ok(self)
+    }
+
+    /// Normalize the keys of every layer to `snake_case` before merging them.
+    ///
+    /// See [`KeyCase`] for how colliding keys are resolved.
+    pub fn key_case(mut self, key_case: KeyCase) -> Self {
+        self.merge_options.key_case = Some(key_case);
+        self
+    }
+
+    /// Register a function post-processing the merged configuration before it is returned.
+    ///
+    /// It receives the table merged from defaults, sources and overrides, and whatever it
+    /// returns becomes the content of the built [`Config`], so it can add computed keys,
+    /// normalize values or drop internal ones. An error aborts the build. Transforms registered
+    /// by several calls run in order, each receiving the result of the previous one.
+    ///
+    /// Transforms run on every build, including the one performed by
+    /// [`check_placeholders`](ConfigBuilder::check_placeholders), which thus checks their
+    /// result. They are kept by the built configuration and run again whenever it merges its
+    /// layers again, e.g. in [`Config::refresh`] or [`Config::refresh_named`], where an error
+    /// leaves the configuration unchanged.
+    pub fn map_transform<F>(mut self, transform: F) -> Self
+    where
+        F: Fn(Map<String, Value>) -> Result<Map<String, Value>> + Send + Sync + 'static,
+    {
+        self.map_transforms.push(MapTransform(Arc::new(transform)));
+        self
+    }
+
+    /// Fail to build unless exactly one of `keys` is present, for mutually exclusive settings
+    /// such as `database.url` and `database.host`.
+    ///
+    /// Keys are paths checked on the merged configuration, present whatever their value, even
+    /// nil. The check is a [`map_transform`](Self::map_transform) and runs in order with the
+    /// others. See [`require_any_of`](Self::require_any_of) to allow several of the keys.
+    pub fn require_one_of(self, keys: &[&str]) -> Self {
+        self.require_present(keys, true)
+    }
+
+    /// Fail to build unless at least one of `keys` is present, like
+    /// [`require_one_of`](Self::require_one_of) but allowing several of them.
+    pub fn require_any_of(self, keys: &[&str]) -> Self {
+        self.require_present(keys, false)
+    }
+
+    fn require_present(self, keys: &[&str], exclusive: bool) -> Self {
+        let keys: Vec<String> = keys.iter().map(|key| key.to_string()).collect();
+        let separator = self.separator();
+
+        self.map_transform(move |map| {
+            let root = Value::new(None, map);
+
+            let mut present = Vec::new();
+            for key in &keys {
+                let expr = Expression::parse_with_separator(key, separator)?;
+                if expr.get(&root).is_some() {
+                    present.push(format!("{:?}", key));
+                }
+            }
+
+            let listed: Vec<String> = keys.iter().map(|key| format!("{:?}", key)).collect();
+            if present.is_empty() {
+                return Err(ConfigError::Message(format!(
+                    "none of the keys {} is set",
+                    listed.join(", ")
+                )));
+            }
+            if exclusive && present.len() > 1 {
+                return Err(ConfigError::Message(format!(
+                    "only one of the keys {} can be set, but {} are",
+                    listed.join(", "),
+                    present.join(", ")
+                )));
+            }
+
+            root.into_table()
+        })
+    }
+
+    /// Separate the segments of keys with `separator` instead of `.`.
+    ///
+    /// This is global for the built [`Config`]: it applies to the keys given to its getters and
+    /// setters, and to those given to this builder after this call. With a separator other than
+    /// `.`, segments are taken literally, so keys containing dots such as domain names can be
+    /// addressed, e.g. `sites/a.com` with `/`. Subscripts like `[0]` keep their meaning.
+    pub fn key_separator(mut self, separator: char) -> Self {
+        self.key_separator = Some(separator);
+        self
+    }
+
+    fn separator(&self) -> char {
+        self.key_separator.unwrap_or('.')
+    }
+
+    /// Register a callback invoked whenever a value is shadowed while building, and again when
+    /// the built [`Config`] merges its layers, e.g. in [`Config::refresh_named`].
+    ///
+    /// It is called synchronously, as layers are merged, with the path of the value,
+    /// the value being replaced and the one replacing it. Their origins are available
+    /// with [`Value::origin`].
+    pub fn on_override<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&str, &Value, &Value) + Send + Sync + 'static,
+    {
+        self.on_override = Some(OverrideHook(Arc::new(hook)));
+        self
+    }
 }
 
 impl ConfigBuilder<DefaultState> {
@@ -185,14 +512,124 @@ impl ConfigBuilder<DefaultState> {
     /// Registers new [`Source`] in this builder.
     ///
     /// Calling this method does not invoke any I/O. [`Source`] is only saved in internal register for later use.
-    pub fn add_source<T>(mut self, source: T) -> Self
+    pub fn add_source<T>(self, source: T) -> Self
+    where
+        T: Source + Send + Sync + 'static,
+    {
+        self.add_source_to_tier(source, Tier::File)
+    }
+
+    /// Registers new [`Source`] in this builder in the precedence `tier`.
+    ///
+    /// The source takes precedence over those of lower tiers, even if they are added after it,
+    /// and over those of the same tier added before it, see [`Tier`]. This way an
+    /// [`Environment`](crate::Environment) in [`Tier::Env`] wins over all the files.
+    pub fn add_source_to_tier<T>(mut self, source: T, tier: Tier) -> Self
     where
         T: Source + Send + Sync + 'static,
     {
-        self.state.sources.push(Box::new(source));
+        self.insert_source(Box::new(source), tier);
         self
     }
 
+    /// Inserts `source` after every source of its `tier` or a lower one, returning its position.
+    fn insert_source(&mut self, source: Box<dyn Source + Send + Sync>, tier: Tier) -> usize {
+        let state = &mut self.state;
+        let index = state
+            .tiers
+            .iter()
+            .position(|&other| other > tier)
+            .unwrap_or(state.tiers.len());
+
+        state.sources.insert(index, source);
+        state.tiers.insert(index, tier);
+        for position in state.names.values_mut() {
+            if *position >= index {
+                *position += 1;
+            }
+        }
+
+        index
+    }
+
+    /// Registers new [`Source`] in this builder under `name`.
+    ///
+    /// The source behaves like one added with [`add_source`](Self::add_source), but it can later
+    /// be collected again on its own with [`Config::refresh_named`].
+    /// Names are not kept if the builder transitions to [`AsyncState`].
+    ///
+    /// # Errors
+    ///
+    /// Fails if a source with the same `name` was already registered.
+    pub fn add_named_source<S, T>(mut self, name: S, source: T) -> Result<Self>
+    where
+        S: Into<String>,
+        T: Source + Send + Sync + 'static,
+    {
+        let name = name.into();
+        if self.state.names.contains_key(&name) {
+            return Err(ConfigError::Message(format!(
+                "a source named {:?} is already registered",
+                name
+            )));
+        }
+
+        let index = self.insert_source(Box::new(source), Tier::File);
+        self.state.names.insert(name, index);
+        Ok(self)
+    }
+
+    /// Registers the files of a configuration profile chosen by the environment variable `env_var`.
+    ///
+    /// The file `default` of `base_dir` is loaded first, then the file named after the value of
+    /// the variable, e.g. `config/default.toml` then `config/production.toml` with `APP_ENV`
+    /// set to `production`. Both are looked up with the extensions of `format`. The base file
+    /// is required while the profile file is optional, and only the base file is loaded when the
+    /// variable is unset or empty. The variable is read when this method is called.
+    ///
+    /// To make the profile file required, add it with [`add_source`](Self::add_source) instead.
+    pub fn add_profile_file<P: AsRef<Path>>(
+        self,
+        base_dir: P,
+        env_var: &str,
+        format: FileFormat,
+    ) -> Self {
+        let base_dir = base_dir.as_ref();
+        let file = |name: &str| {
+            let path = base_dir.join(name);
+            File::new(&path.to_string_lossy(), format)
+        };
+
+        let builder = self.add_source(file("default"));
+
+        match env::var(env_var) {
+            Ok(ref profile) if !profile.is_empty() => {
+                builder.add_source(file(profile).required(false))
+            }
+            _ => builder,
+        }
+    }
+
+    /// Registers the files at `paths` in order as optional sources parsed as `format`.
+    ///
+    /// This is the same as adding each with [`File::optional`]: later files override the keys
+    /// of earlier ones and missing files are skipped, e.g. a `local.toml` present only on
+    /// some machines.
+    pub fn add_layers<P: AsRef<Path>>(self, paths: &[P], format: FileFormat) -> Self {
+        paths.iter().fold(self, |builder, path| {
+            builder.add_source(File::optional(&path.as_ref().to_string_lossy(), format))
+        })
+    }
+
+    /// Registers the files named `basename` with the extensions of each of `formats`, such as
+    /// `config.toml` and `config.json`, merged in the order of `formats` as a single source.
+    ///
+    /// Files that do not exist are skipped, so only the formats present are merged, later ones
+    /// overriding earlier ones. If `required`, collecting fails when none of the files exists.
+    pub fn add_basename(self, basename: &str, formats: &[FileFormat], required: bool) -> Self {
+        self.add_source(FileSet::new(basename, formats, required))
+    }
+
     /// Registers new [`AsyncSource`] in this builder and forces transition to [`AsyncState`].
     ///
     /// Calling this method does not invoke any I/O. [`AsyncSource`] is only saved in internal register for later use.
@@ -211,6 +648,11 @@ impl ConfigBuilder<DefaultState> {
             },
             defaults: self.defaults,
             overrides: self.overrides,
+            merge_options: self.merge_options,
+            on_override: self.on_override,
+            map_transforms: self.map_transforms,
+            de_options: self.de_options,
+            key_separator: self.key_separator,
         };
 
         async_state.add_async_source(source)
@@ -225,7 +667,22 @@ impl ConfigBuilder<DefaultState> {
     /// If source collection fails, be it technical reasons or related to inability to read data as `Config` for different reasons,
     /// this method returns error.
     pub fn build(self) -> Result<Config> {
-        Self::build_internal(self.defaults, self.overrides, &self.state.sources)
+        let de_options = self.de_options.clone();
+        let separator = self.separator();
+        let transforms = &self.map_transforms;
+
+        Self::build_internal(
+            self.defaults,
+            self.overrides,
+            self.state.sources,
+            self.state.names,
+            self.merge_options,
+            self.on_override.as_ref(),
+            false,
+        )
+        .map_err(|mut errors| errors.remove(0))
+        .and_then(|config| apply_transforms(config, transforms))
+        .map(|config| config.with_de_options(de_options).with_separator(separator))
     }
 
     /// Reads all registered [`Source`]s.
@@ -237,34 +694,198 @@ impl ConfigBuilder<DefaultState> {
     /// If source collection fails, be it technical reasons or related to inability to read data as `Config` for different reasons,
     /// this method returns error.
     pub fn build_cloned(&self) -> Result<Config> {
+        let de_options = self.de_options.clone();
+        let separator = self.separator();
+        let transforms = &self.map_transforms;
+
         Self::build_internal(
             self.defaults.clone(),
             self.overrides.clone(),
-            &self.state.sources,
+            self.state.sources.clone(),
+            self.state.names.clone(),
+            self.merge_options.clone(),
+            self.on_override.as_ref(),
+            false,
+        )
+        .map_err(|mut errors| errors.remove(0))
+        .and_then(|config| apply_transforms(config, transforms))
+        .map(|config| config.with_de_options(de_options).with_separator(separator))
+    }
+
+    /// Reads all registered [`Source`]s like [`build`](Self::build), but collects every source
+    /// even after one fails, to report all their errors at once.
+    ///
+    /// Useful with many files, where fixing one malformed file would otherwise only reveal
+    /// the next one. Transforms added with [`map_transform`](Self::map_transform) only run if
+    /// all the sources were collected.
+    ///
+    /// # Errors
+    /// Fails with the errors of all the sources that could not be collected, in the order the
+    /// sources were added, or with the error of a transform.
+    pub fn build_collecting_errors(self) -> std::result::Result<Config, Vec<ConfigError>> {
+        let de_options = self.de_options.clone();
+        let separator = self.separator();
+        let transforms = &self.map_transforms;
+
+        Self::build_internal(
+            self.defaults,
+            self.overrides,
+            self.state.sources,
+            self.state.names,
+            self.merge_options,
+            self.on_override.as_ref(),
+            true,
+        )
+        .and_then(|config| apply_transforms(config, transforms).map_err(|error| vec![error]))
+        .map(|config| config.with_de_options(de_options).with_separator(separator))
+    }
+
+    /// Checks that every `${...}` placeholder in the string values of the merged configuration
+    /// refers to an existing key, like `${database.host}`, or to a set environment variable.
+    ///
+    /// No substitution is performed and the builder is left untouched, sources are read
+    /// as in [`build_cloned`](Self::build_cloned).
+    ///
+    /// # Errors
+    /// Fails if the sources cannot be read, or if some placeholders do not resolve, with an
+    /// error listing them.
+    pub fn check_placeholders(&self) -> Result<()> {
+        check_placeholders(&self.build_cloned()?)
+    }
+
+    /// Builds the configuration like [`build_cloned`](Self::build_cloned) to report each of its
+    /// keys with its final value and the values it shadows, e.g. to show users where their
+    /// settings come from.
+    ///
+    /// The origin of each value is available with [`Value::origin`]; defaults and overrides
+    /// have none. The [`on_override`](Self::on_override) hook is not called.
+    ///
+    /// # Errors
+    /// Fails like [`build_cloned`](Self::build_cloned).
+    pub fn explain(&self) -> Result<ConfigExplanation> {
+        let shadowed = Arc::new(Mutex::new(Map::<String, Vec<Value>>::new()));
+        let record = shadowed.clone();
+        let hook = OverrideHook(Arc::new(move |key: &str, old: &Value, _: &Value| {
+            record
+                .lock()
+                .unwrap_or_else(|err| err.into_inner())
+                .entry(key.to_string())
+                .or_default()
+                .push(old.clone());
+        }));
+
+        let config = Self::build_internal(
+            self.defaults.clone(),
+            self.overrides.clone(),
+            self.state.sources.clone(),
+            self.state.names.clone(),
+            self.merge_options.clone(),
+            Some(&hook),
+            false,
         )
+        .map_err(|mut errors| errors.remove(0))
+        .and_then(|config| apply_transforms(config, &self.map_transforms))?;
+
+        let shadowed = std::mem::take(&mut *shadowed.lock().unwrap_or_else(|err| err.into_inner()));
+        Ok(ConfigExplanation::new(config.cache, shadowed))
+    }
+
+    /// Checks that the defaults, overrides and sources provide a key for every required field
+    /// of `T`, without collecting the sources.
+    ///
+    /// Fields are required unless they are an `Option` or have a `#[serde(default)]`, and only
+    /// the top-level fields of `T` are checked. Keys of the sources are those reported by
+    /// [`Source::known_keys`]: if a source cannot tell them, the check is inconclusive and passes.
+    ///
+    /// # Errors
+    /// Fails if `T` is not a struct, or with an error listing the required fields without a key.
+    pub fn check_required<T: DeserializeOwned>(&self) -> Result<()> {
+        let mut keys: Vec<String> = self
+            .defaults
+            .keys()
+            .chain(self.overrides.keys())
+            .map(|expr| expr.to_string())
+            .collect();
+
+        match self.state.sources.known_keys() {
+            Some(source_keys) => keys.extend(source_keys),
+            None => return Ok(()),
+        }
+
+        let missing: Vec<String> = crate::schema::required_fields::<T>()?
+            .into_iter()
+            .filter(|names| {
+                // The field may be provided under any of its aliases
+                !names.iter().any(|&field| {
+                    let nested = format!("{}.", field);
+                    let indexed = format!("{}[", field);
+
+                    keys.iter().any(|key| {
+                        key == field || key.starts_with(&nested) || key.starts_with(&indexed)
+                    })
+                })
+            })
+            .map(|names| format!("{:?}", names[0]))
+            .collect();
+
+        if missing.is_empty() {
+            Ok(())
+        } else {
+            Err(ConfigError::Message(format!(
+                "no source provides the required fields: {}",
+                missing.join(", ")
+            )))
+        }
     }
 
+    /// Collects the sources and merges them, stopping at the first error unless `all_errors`.
     fn build_internal(
         defaults: Map<Expression, Value>,
         overrides: Map<Expression, Value>,
-        sources: &[Box<dyn Source + Send + Sync>],
-    ) -> Result<Config> {
-        let mut cache: Value = Map::<String, Value>::new().into();
+        sources: Vec<Box<dyn Source + Send + Sync>>,
+        names: Map<String, usize>,
+        merge_options: MergeOptions,
+        on_override: Option<&OverrideHook>,
+        all_errors: bool,
+    ) -> std::result::Result<Config, Vec<ConfigError>> {
+        let key_case = merge_options.key_case;
 
-        // Add defaults
-        for (key, val) in defaults.into_iter() {
-            key.set(&mut cache, val);
+        let mut layers = Vec::with_capacity(sources.len());
+        let mut comments = Map::new();
+        let mut errors = Vec::new();
+
+        for source in sources.iter() {
+            let collected = collect_layer(source.as_ref(), key_case)
+                .and_then(|layer| Ok((layer, collect_comments(source.as_ref(), key_case)?)));
+
+            match collected {
+                Ok((layer, source_comments)) => {
+                    layers.push(layer);
+                    comments.extend(source_comments);
+                }
+                Err(error) if all_errors => errors.push(error),
+                Err(error) => return Err(vec![error]),
+            }
         }
 
-        // Add sources
-        sources.collect_to(&mut cache)?;
+        if !errors.is_empty() {
+            return Err(errors);
+        }
 
-        // Add overrides
-        for (key, val) in overrides.into_iter() {
-            key.set(&mut cache, val);
+        if merge_options.strict_types {
+            check_types(&layers).map_err(|error| vec![error])?;
         }
 
-        Ok(Config::new(cache))
+        Ok(Config::from_layers(
+            defaults,
+            overrides,
+            sources,
+            names,
+            layers,
+            merge_options,
+            on_override,
+        )
+        .with_comments(comments))
     }
 }
 
@@ -302,7 +923,20 @@ impl ConfigBuilder<AsyncState> {
     /// If source collection fails, be it technical reasons or related to inability to read data as `Config` for different reasons,
     /// this method returns error.
     pub async fn build(self) -> Result<Config> {
-        Self::build_internal(self.defaults, self.overrides, &self.state.sources).await
+        let de_options = self.de_options.clone();
+        let separator = self.separator();
+        let transforms = &self.map_transforms;
+
+        Self::build_internal(
+            self.defaults,
+            self.overrides,
+            &self.state.sources,
+            self.merge_options,
+            self.on_override.as_ref(),
+        )
+        .await
+        .and_then(|config| apply_transforms(config, transforms))
+        .map(|config| config.with_de_options(de_options).with_separator(separator))
     }
 
     /// Reads all registered defaults, [`Source`]s, [`AsyncSource`]s and overrides.
@@ -314,38 +948,386 @@ impl ConfigBuilder<AsyncState> {
     /// If source collection fails, be it technical reasons or related to inability to read data as `Config` for different reasons,
     /// this method returns error.
     pub async fn build_cloned(&self) -> Result<Config> {
+        let de_options = self.de_options.clone();
+        let separator = self.separator();
+        let transforms = &self.map_transforms;
+
         Self::build_internal(
             self.defaults.clone(),
             self.overrides.clone(),
             &self.state.sources,
+            self.merge_options.clone(),
+            self.on_override.as_ref(),
         )
         .await
+        .and_then(|config| apply_transforms(config, transforms))
+        .map(|config| config.with_de_options(de_options).with_separator(separator))
+    }
+
+    /// Checks that every `${...}` placeholder in the string values of the merged configuration
+    /// refers to an existing key or to a set environment variable.
+    ///
+    /// See [`ConfigBuilder::<DefaultState>::check_placeholders`](ConfigBuilder::check_placeholders).
+    pub async fn check_placeholders(&self) -> Result<()> {
+        check_placeholders(&self.build_cloned().await?)
     }
 
     async fn build_internal(
         defaults: Map<Expression, Value>,
         overrides: Map<Expression, Value>,
         sources: &[SourceType],
+        merge_options: MergeOptions,
+        on_override: Option<&OverrideHook>,
     ) -> Result<Config> {
-        let mut cache: Value = Map::<String, Value>::new().into();
+        let key_case = merge_options.key_case;
 
-        // Add defaults
-        for (key, val) in defaults.into_iter() {
-            key.set(&mut cache, val);
-        }
+        let mut layers = Vec::with_capacity(sources.len());
+        let mut comments = Map::new();
 
         for source in sources.iter() {
-            match source {
-                SourceType::Sync(source) => source.collect_to(&mut cache)?,
-                SourceType::Async(source) => source.collect_to(&mut cache).await?,
+            layers.push(match source {
+                SourceType::Sync(source) => {
+                    comments.extend(collect_comments(source.as_ref(), key_case)?);
+                    collect_layer(source.as_ref(), key_case)?
+                }
+                SourceType::Async(source) => convert_layer(source.collect().await?, key_case),
+            });
+        }
+
+        if merge_options.strict_types {
+            check_types(&layers)?;
+        }
+
+        Ok(Config::new(merge_layers(
+            &defaults,
+            &layers,
+            &overrides,
+            &merge_options,
+            on_override,
+        ))
+        .with_comments(comments))
+    }
+}
+
+fn check_placeholders(config: &Config) -> Result<()> {
+    let unresolved = placeholder::unresolved(&config.cache);
+
+    if unresolved.is_empty() {
+        return Ok(());
+    }
+
+    let names: Vec<String> = unresolved
+        .iter()
+        .map(|name| format!("${{{}}}", name))
+        .collect();
+
+    Err(ConfigError::Message(format!(
+        "unresolved placeholders: {}",
+        names.join(", ")
+    )))
+}
+
+/// Fails if two layers set values of different types at the same path.
+pub(crate) fn check_types(layers: &[Map<String, Value>]) -> Result<()> {
+    let mut seen = Map::new();
+
+    for layer in layers {
+        let mut tree: Value = Map::<String, Value>::new().into();
+        merge_layer(&mut tree, layer, &[], None);
+
+        if let ValueKind::Table(table) = tree.kind {
+            for (key, value) in table {
+                check_type(&mut seen, key, value)?;
             }
         }
+    }
 
-        // Add overrides
-        for (key, val) in overrides.into_iter() {
-            key.set(&mut cache, val);
+    Ok(())
+}
+
+/// Records the type of `value` at `path` and its children in `seen`, failing if a previous layer
+/// recorded another one.
+fn check_type(
+    seen: &mut Map<String, (&'static str, Option<String>)>,
+    path: String,
+    value: Value,
+) -> Result<()> {
+    let kind = match value.kind {
+        ValueKind::Nil => return Ok(()),
+        ValueKind::Boolean(_) => "boolean",
+        ValueKind::Integer(_) => "integer",
+        ValueKind::Float(_) => "float",
+        ValueKind::Number(_) => "number",
+        ValueKind::String(_) => "string",
+        ValueKind::Table(_) => "table",
+        ValueKind::Array(_) => "array",
+    };
+    let origin = value.origin().map(String::from);
+
+    if let Some((previous, previous_origin)) = seen.get(&path) {
+        if *previous != kind {
+            let describe = |origin: &Option<String>| match origin {
+                Some(origin) => format!("{:?}", origin),
+                None => "an unknown origin".into(),
+            };
+
+            return Err(ConfigError::Message(format!(
+                "conflicting types for key {:?}: {} from {} and {} from {}",
+                path,
+                previous,
+                describe(previous_origin),
+                kind,
+                describe(&origin)
+            )));
+        }
+    }
+
+    seen.insert(path.clone(), (kind, origin));
+
+    if let ValueKind::Table(table) = value.kind {
+        for (key, child) in table {
+            check_type(seen, format!("{}.{}", path, key), child)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Options changing how the layers of a configuration are merged.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct MergeOptions {
+    /// Naming convention of the keys to normalize in every layer
+    pub(crate) key_case: Option<KeyCase>,
+
+    /// Whether defaults only fill the leaves missing after the other layers
+    pub(crate) deep_defaults: bool,
+
+    /// Whether sources disagreeing on the type of a key are an error
+    pub(crate) strict_types: bool,
+
+    /// Arrays of tables merged element by element across sources
+    pub(crate) array_keys: Vec<ArrayMergeKey>,
+}
+
+/// Path of an array of tables merged across sources by the value of `field` in each element.
+#[derive(Clone, Debug)]
+pub(crate) struct ArrayMergeKey {
+    path: Expression,
+    field: String,
+}
+
+/// Merges defaults, the values collected from each source and overrides, in this order.
+///
+/// With `deep_defaults`, defaults are instead merged last and only fill the missing leaves.
+pub(crate) fn merge_layers(
+    defaults: &Map<Expression, Value>,
+    layers: &[Map<String, Value>],
+    overrides: &Map<Expression, Value>,
+    options: &MergeOptions,
+    on_override: Option<&OverrideHook>,
+) -> Value {
+    let MergeOptions {
+        key_case,
+        deep_defaults,
+        ref array_keys,
+        ..
+    } = *options;
+    let mut cache: Value = Map::<String, Value>::new().into();
+
+    // Add defaults
+    if !deep_defaults {
+        set_values(&mut cache, defaults, key_case, on_override);
+    }
+
+    // Add sources
+    for layer in layers {
+        merge_layer(&mut cache, layer, array_keys, on_override);
+    }
+
+    // Add overrides
+    set_values(&mut cache, overrides, key_case, on_override);
+
+    // Fill the leaves still missing with defaults
+    if deep_defaults {
+        let mut tree: Value = Map::<String, Value>::new().into();
+        set_values(&mut tree, defaults, key_case, None);
+        fill_missing(&mut cache, tree);
+    }
+
+    cache
+}
+
+/// Sets the values collected from a source over those already in `cache`.
+///
+/// The arrays at `array_keys` set by the source are merged with those in `cache` by key
+/// instead of replacing them.
+pub(crate) fn merge_layer(
+    cache: &mut Value,
+    layer: &Map<String, Value>,
+    array_keys: &[ArrayMergeKey],
+    on_override: Option<&OverrideHook>,
+) {
+    // Arrays to merge by key, along with the array they are merged into
+    let mut arrays = Vec::new();
+    if !array_keys.is_empty() {
+        let mut tree: Value = Map::<String, Value>::new().into();
+        merge_layer(&mut tree, layer, &[], None);
+
+        for array_key in array_keys {
+            let incoming = match array_key.path.clone().get(&tree) {
+                Some(value) if matches!(value.kind, ValueKind::Array(_)) => value.clone(),
+                _ => continue,
+            };
+
+            if let Some(ValueKind::Array(existing)) = array_key
+                .path
+                .clone()
+                .get(cache)
+                .map(|value| value.kind.clone())
+            {
+                arrays.push((array_key, existing, incoming));
+            }
         }
+    }
+
+    for (key, val) in layer {
+        // Set directly anyway if the key is not a valid path
+        let expr =
+            Expression::from_str(key).unwrap_or_else(|_| Expression::Identifier(key.to_string()));
+
+        set_value(cache, &expr, val.clone(), on_override);
+    }
+
+    for (array_key, existing, incoming) in arrays {
+        let merged = merge_by_key(existing, incoming, &array_key.field);
+        array_key.path.set(cache, merged);
+    }
+}
+
+/// Merges the tables of `incoming` into the elements of `existing` with the same `field`,
+/// appending the others.
+fn merge_by_key(mut existing: Vec<Value>, mut incoming: Value, field: &str) -> Value {
+    let elements = match std::mem::replace(&mut incoming.kind, ValueKind::Nil) {
+        ValueKind::Array(elements) => elements,
+        _ => Vec::new(),
+    };
+
+    // Only match the elements from previous layers, not those appended from `incoming`
+    let previous = existing.len();
+
+    for element in elements {
+        let position = element_key(&element, field).and_then(|id| {
+            existing[..previous]
+                .iter()
+                .position(|other| element_key(other, field) == Some(id))
+        });
+
+        match position {
+            Some(index) => {
+                if let ValueKind::Table(table) = element.kind {
+                    for (key, value) in table {
+                        Expression::Identifier(key).set(&mut existing[index], value);
+                    }
+                }
+            }
+
+            None => existing.push(element),
+        }
+    }
+
+    // Keep the origin of the source setting the array
+    incoming.kind = ValueKind::Array(existing);
+    incoming
+}
+
+/// Value of `field` identifying `element` in an array merged by key, if it has one.
+fn element_key<'a>(element: &'a Value, field: &str) -> Option<&'a ValueKind> {
+    match element.kind {
+        ValueKind::Table(ref table) => table
+            .get(field)
+            .map(|value| &value.kind)
+            .filter(|kind| !matches!(kind, ValueKind::Nil)),
+        _ => None,
+    }
+}
+
+/// Sets the leaves of `defaults` that are absent or nil in `target`, recursing into tables.
+fn fill_missing(target: &mut Value, defaults: Value) {
+    if let ValueKind::Nil = target.kind {
+        *target = defaults;
+        return;
+    }
+
+    // Anything else than a table set by a higher layer wins
+    if let (ValueKind::Table(table), ValueKind::Table(defaults)) = (&mut target.kind, defaults.kind)
+    {
+        for (key, default) in defaults {
+            match table.get_mut(&key) {
+                Some(value) => fill_missing(value, default),
+                None => {
+                    table.insert(key, default);
+                }
+            }
+        }
+    }
+}
+
+fn set_value(
+    cache: &mut Value,
+    expr: &Expression,
+    value: Value,
+    on_override: Option<&OverrideHook>,
+) {
+    if let Some(hook) = on_override {
+        hook.report(cache, expr, &value);
+    }
+
+    expr.set(cache, value);
+}
+
+fn set_values(
+    cache: &mut Value,
+    values: &Map<Expression, Value>,
+    key_case: Option<KeyCase>,
+    on_override: Option<&OverrideHook>,
+) {
+    for (key, val) in values.iter() {
+        match key_case {
+            Some(key_case) => set_value(
+                cache,
+                &key_case.convert_expression(key.clone()),
+                key_case.convert_value(val.clone()),
+                on_override,
+            ),
+            None => set_value(cache, key, val.clone(), on_override),
+        }
+    }
+}
+
+/// Collects a single source, normalizing its keys if requested.
+pub(crate) fn collect_layer(
+    source: &dyn Source,
+    key_case: Option<KeyCase>,
+) -> Result<Map<String, Value>> {
+    Ok(convert_layer(source.collect()?, key_case))
+}
+
+/// Collects the comments of a single source, normalizing their keys like its values.
+fn collect_comments(source: &dyn Source, key_case: Option<KeyCase>) -> Result<Map<String, String>> {
+    let comments = source.comments()?;
+
+    Ok(match key_case {
+        Some(key_case) => comments
+            .into_iter()
+            .map(|(key, comment)| (key_case.convert(&key), comment))
+            .collect(),
+        None => comments,
+    })
+}
 
-        Ok(Config::new(cache))
+fn convert_layer(values: Map<String, Value>, key_case: Option<KeyCase>) -> Map<String, Value> {
+    match key_case {
+        Some(key_case) => key_case.convert_table(values),
+        None => values,
     }
 }
@@ -0,0 +1,120 @@
+use crate::map::Map;
+use crate::path::Expression;
+use crate::value::{Value, ValueKind};
+
+/// Naming convention of configuration keys that should be normalized to `snake_case`.
+///
+/// Set with [`ConfigBuilder::key_case`](crate::ConfigBuilder::key_case) so that keys such as
+/// `maxRetries` or `max-retries` match a `max_retries` field when deserializing.
+///
+/// Keys are normalized per layer (defaults, each source, overrides) before it is merged,
+/// so the usual precedence between layers is kept. When two keys of the same table
+/// normalize to the same name, a key that was already in `snake_case` wins; otherwise
+/// the lexicographically greatest original key wins.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum KeyCase {
+    /// `camelCase` and `PascalCase` keys, e.g. `maxRetries` becomes `max_retries`.
+    CamelToSnake,
+
+    /// `kebab-case` keys, e.g. `max-retries` becomes `max_retries`.
+    KebabToSnake,
+}
+
+impl KeyCase {
+    pub(crate) fn convert(self, key: &str) -> String {
+        match self {
+            KeyCase::CamelToSnake => camel_to_snake(key),
+            KeyCase::KebabToSnake => key.replace('-', "_"),
+        }
+    }
+
+    pub(crate) fn convert_value(self, mut value: Value) -> Value {
+        value.kind = match std::mem::take(&mut value.kind) {
+            ValueKind::Table(table) => ValueKind::Table(self.convert_table(table)),
+            ValueKind::Array(array) => {
+                ValueKind::Array(array.into_iter().map(|v| self.convert_value(v)).collect())
+            }
+            kind => kind,
+        };
+        value
+    }
+
+    /// Converts the keys of `table`, keeping their order; colliding keys take the place of the
+    /// first of them.
+    pub(crate) fn convert_table(self, table: Map<String, Value>) -> Map<String, Value> {
+        // Original key of each converted one, to settle collisions
+        let mut originals: Map<String, String> = Map::new();
+        let mut converted = Map::new();
+
+        for (key, value) in table {
+            let new_key = self.convert(&key);
+            let wins = match originals.get(&new_key) {
+                Some(previous) => self.precedence(&key) > self.precedence(previous),
+                None => true,
+            };
+
+            if wins {
+                converted.insert(new_key.clone(), self.convert_value(value));
+                originals.insert(new_key, key);
+            }
+        }
+
+        converted
+    }
+
+    /// Orders the keys colliding after conversion, the greatest winning: untouched keys first,
+    /// then the lexicographically greatest.
+    fn precedence(self, key: &str) -> (bool, &str) {
+        (self.convert(key) == key, key)
+    }
+
+    pub(crate) fn convert_expression(self, expr: Expression) -> Expression {
+        match expr {
+            Expression::Identifier(id) => Expression::Identifier(self.convert(&id)),
+            Expression::Child(expr, key) => {
+                Expression::Child(Box::new(self.convert_expression(*expr)), self.convert(&key))
+            }
+            Expression::Subscript(expr, index) => {
+                Expression::Subscript(Box::new(self.convert_expression(*expr)), index)
+            }
+        }
+    }
+}
+
+fn camel_to_snake(key: &str) -> String {
+    let chars: Vec<char> = key.chars().collect();
+    let mut result = String::with_capacity(key.len());
+
+    for (i, &c) in chars.iter().enumerate() {
+        if c.is_uppercase() && i > 0 {
+            let prev = chars[i - 1];
+            let next_is_lower = matches!(chars.get(i + 1), Some(n) if n.is_lowercase());
+
+            // Split `maxRetries` and the end of acronyms like `HTTPPort`
+            if prev.is_lowercase()
+                || prev.is_ascii_digit()
+                || (prev.is_uppercase() && next_is_lower)
+            {
+                result.push('_');
+            }
+        }
+
+        result.extend(c.to_lowercase());
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_camel_to_snake() {
+        assert_eq!(camel_to_snake("maxRetries"), "max_retries");
+        assert_eq!(camel_to_snake("MaxRetries"), "max_retries");
+        assert_eq!(camel_to_snake("HTTPPort"), "http_port");
+        assert_eq!(camel_to_snake("redis.maxRetries"), "redis.max_retries");
+        assert_eq!(camel_to_snake("max_retries"), "max_retries");
+    }
+}
@@ -1,15 +1,26 @@
+use std::any::{Any, TypeId};
+use std::fmt;
 use std::fmt::Debug;
-
-use crate::builder::{ConfigBuilder, DefaultState};
-use serde::de::Deserialize;
+use std::ops::Deref;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::builder::{
+    check_types, collect_layer, merge_layer, merge_layers, transform_cache, ConfigBuilder,
+    DefaultState, MapTransform, MergeOptions, OverrideHook,
+};
+use serde::de::{Deserialize, DeserializeOwned, DeserializeSeed};
 use serde::ser::Serialize;
 
+use crate::de::{DeOptions, ValueDeserializer};
+use crate::env::Environment;
 use crate::error::*;
 use crate::map::Map;
 use crate::path;
 use crate::ser::ConfigSerializer;
 use crate::source::Source;
-use crate::value::{Table, Value};
+use crate::units;
+use crate::value::{Table, Value, ValueKind};
 
 /// A prioritized configuration repository. It maintains a set of
 /// configuration sources, fetches values to populate those, and provides
@@ -20,6 +31,32 @@ pub struct Config {
     overrides: Map<path::Expression, Value>,
     sources: Vec<Box<dyn Source + Send + Sync>>,
 
+    /// Positions of named sources in `sources`.
+    names: Map<String, usize>,
+
+    /// Last collected values of each source in `sources`.
+    layers: Vec<Map<String, Value>>,
+
+    merge_options: MergeOptions,
+
+    /// Transforms run over the merged configuration, from [`ConfigBuilder::map_transform`].
+    map_transforms: Vec<MapTransform>,
+
+    /// Hook called when merging shadows a value, from [`ConfigBuilder::on_override`].
+    on_override: Option<OverrideHook>,
+
+    de_options: DeOptions,
+
+    /// Separator between the segments of keys, `.` unless set with
+    /// [`ConfigBuilder::key_separator`].
+    separator: char,
+
+    /// Comments of the keys, by path, collected from the sources.
+    comments: Map<String, String>,
+
+    /// Values deserialized by [`Config::typed`].
+    typed: TypedCache,
+
     /// Root of the cached configuration.
     pub cache: Value,
 }
@@ -30,6 +67,15 @@ impl Default for Config {
             defaults: Default::default(),
             overrides: Default::default(),
             sources: Default::default(),
+            names: Default::default(),
+            layers: Default::default(),
+            merge_options: MergeOptions::default(),
+            map_transforms: Vec::new(),
+            on_override: None,
+            de_options: DeOptions::default(),
+            separator: '.',
+            comments: Map::new(),
+            typed: TypedCache::default(),
             cache: Value::new(None, Table::new()),
         }
     }
@@ -43,6 +89,62 @@ impl Config {
         }
     }
 
+    pub(crate) fn from_layers(
+        defaults: Map<path::Expression, Value>,
+        overrides: Map<path::Expression, Value>,
+        sources: Vec<Box<dyn Source + Send + Sync>>,
+        names: Map<String, usize>,
+        layers: Vec<Map<String, Value>>,
+        merge_options: MergeOptions,
+        on_override: Option<&OverrideHook>,
+    ) -> Self {
+        let cache = merge_layers(&defaults, &layers, &overrides, &merge_options, on_override);
+
+        Config {
+            defaults,
+            overrides,
+            sources,
+            names,
+            layers,
+            merge_options,
+            map_transforms: Vec::new(),
+            on_override: on_override.cloned(),
+            de_options: DeOptions::default(),
+            separator: '.',
+            comments: Map::new(),
+            typed: TypedCache::default(),
+            cache,
+        }
+    }
+
+    pub(crate) fn with_de_options(mut self, de_options: DeOptions) -> Self {
+        self.de_options = de_options;
+        self
+    }
+
+    pub(crate) fn with_separator(mut self, separator: char) -> Self {
+        self.separator = separator;
+        self
+    }
+
+    pub(crate) fn with_transforms(mut self, map_transforms: Vec<MapTransform>) -> Self {
+        self.map_transforms = map_transforms;
+        self
+    }
+
+    pub(crate) fn with_comments(mut self, comments: Map<String, String>) -> Self {
+        self.comments = comments;
+        self
+    }
+
+    fn parse_key(&self, key: &str) -> Result<path::Expression> {
+        path::Expression::parse_with_separator(key, self.separator)
+    }
+
+    pub(crate) fn de_options(&self) -> DeOptions {
+        self.de_options.clone()
+    }
+
     /// Creates new [`ConfigBuilder`] instance
     pub fn builder() -> ConfigBuilder<DefaultState> {
         ConfigBuilder::<DefaultState>::default()
@@ -79,29 +181,145 @@ impl Config {
     /// data from added sources.
     ///
     /// Configuration is automatically refreshed after a mutation
-    /// operation (`set`, `merge`, `set_default`, etc.).
+    /// operation (`set`, `merge`, `set_default`, etc.). Sources are merged with the options
+    /// and transforms of the builder, like when building; if that fails, the cache is left
+    /// unchanged.
     #[deprecated(since = "0.12.0", note = "please use 'ConfigBuilder' instead")]
     pub fn refresh(&mut self) -> Result<&mut Config> {
-        self.cache = {
-            let mut cache: Value = Map::<String, Value>::new().into();
+        let key_case = self.merge_options.key_case;
+        let layers = self
+            .sources
+            .iter()
+            .map(|source| collect_layer(source.as_ref(), key_case))
+            .collect::<Result<_>>()?;
+
+        let previous = std::mem::replace(&mut self.layers, layers);
+        if let Err(error) = self.remerge() {
+            self.layers = previous;
+            return Err(error);
+        }
 
-            // Add defaults
-            for (key, val) in self.defaults.iter() {
-                key.set(&mut cache, val.clone());
-            }
+        Ok(self)
+    }
 
-            // Add sources
-            self.sources.collect_to(&mut cache)?;
+    /// Collect again the source registered as `name` with
+    /// [`ConfigBuilder::add_named_source`] and update the configuration.
+    ///
+    /// Other sources are not collected; their previously collected values are reused.
+    ///
+    /// # Errors
+    ///
+    /// Fails if no source is named `name`, if collecting it fails, or if merging it fails
+    /// like when building, in which case the configuration is left unchanged.
+    pub fn refresh_named(&mut self, name: &str) -> Result<&mut Config> {
+        let index = *self
+            .names
+            .get(name)
+            .ok_or_else(|| ConfigError::Message(format!("no source named {:?}", name)))?;
+
+        let layer = collect_layer(self.sources[index].as_ref(), self.merge_options.key_case)?;
+        let previous = std::mem::replace(&mut self.layers[index], layer);
+        if let Err(error) = self.remerge() {
+            self.layers[index] = previous;
+            return Err(error);
+        }
 
-            // Add overrides
-            for (key, val) in self.overrides.iter() {
-                key.set(&mut cache, val.clone());
-            }
+        Ok(self)
+    }
 
-            cache
-        };
+    /// Replace all the defaults with `defaults` and update the configuration.
+    ///
+    /// Sources are not collected again; their previously collected values are reused, and
+    /// they and the overrides still take precedence over the new defaults. Keys are paths such
+    /// as `database.url`.
+    ///
+    /// # Errors
+    ///
+    /// Fails if a key is not a valid path, or if merging fails like when building, in which
+    /// case the configuration is left unchanged.
+    pub fn set_default_layer(&mut self, defaults: Map<String, Value>) -> Result<()> {
+        let defaults = self.parse_layer(defaults)?;
+        let previous = std::mem::replace(&mut self.defaults, defaults);
+        if let Err(error) = self.remerge() {
+            self.defaults = previous;
+            return Err(error);
+        }
 
-        Ok(self)
+        Ok(())
+    }
+
+    /// Replace all the overrides with `overrides` and update the configuration.
+    ///
+    /// Sources are not collected again; their previously collected values are reused and
+    /// the defaults are kept. Keys are paths such as `database.url`.
+    ///
+    /// # Errors
+    ///
+    /// Fails if a key is not a valid path, or if merging fails like when building, in which
+    /// case the configuration is left unchanged.
+    pub fn set_override_layer(&mut self, overrides: Map<String, Value>) -> Result<()> {
+        let overrides = self.parse_layer(overrides)?;
+        let previous = std::mem::replace(&mut self.overrides, overrides);
+        if let Err(error) = self.remerge() {
+            self.overrides = previous;
+            return Err(error);
+        }
+
+        Ok(())
+    }
+
+    fn parse_layer(&self, layer: Map<String, Value>) -> Result<Map<path::Expression, Value>> {
+        layer
+            .into_iter()
+            .map(|(key, value)| Ok((self.parse_key(&key)?, value)))
+            .collect()
+    }
+
+    /// Merge again the defaults, the last collected values of the sources and the overrides,
+    /// with the options and transforms of the builder.
+    ///
+    /// The cache is left untouched if merging fails.
+    fn remerge(&mut self) -> Result<()> {
+        if self.merge_options.strict_types {
+            check_types(&self.layers)?;
+        }
+
+        let cache = merge_layers(
+            &self.defaults,
+            &self.layers,
+            &self.overrides,
+            &self.merge_options,
+            self.on_override.as_ref(),
+        );
+        self.cache = transform_cache(cache, &self.map_transforms)?;
+        self.typed.clear();
+
+        Ok(())
+    }
+
+    /// Collects `env` and merges its values over the current configuration, above everything
+    /// else including overrides.
+    ///
+    /// Useful when the environment is only known after the configuration was built, e.g.
+    /// after some setup. Keys are normalized like those of the other sources if the builder
+    /// was given a [`key_case`](ConfigBuilder::key_case). The values are only merged into the
+    /// current configuration: a later refresh merges the sources again without them.
+    ///
+    /// # Errors
+    ///
+    /// Fails if collecting `env` fails, e.g. because of a missing required variable; the
+    /// configuration is left untouched then.
+    pub fn apply_env(&mut self, env: Environment) -> Result<()> {
+        let layer = collect_layer(&env, self.merge_options.key_case)?;
+        self.typed.clear();
+        merge_layer(
+            &mut self.cache,
+            &layer,
+            &self.merge_options.array_keys,
+            self.on_override.as_ref(),
+        );
+
+        Ok(())
     }
 
     /// Set a default `value` at `key`
@@ -110,7 +328,7 @@ impl Config {
     where
         T: Into<Value>,
     {
-        self.defaults.insert(key.parse()?, value.into());
+        self.defaults.insert(self.parse_key(key)?, value.into());
 
         #[allow(deprecated)]
         self.refresh()
@@ -129,7 +347,7 @@ impl Config {
     where
         T: Into<Value>,
     {
-        self.overrides.insert(key.parse()?, value.into());
+        self.overrides.insert(self.parse_key(key)?, value.into());
 
         #[allow(deprecated)]
         self.refresh()
@@ -137,7 +355,8 @@ impl Config {
 
     #[deprecated(since = "0.12.0", note = "please use 'ConfigBuilder' instead")]
     pub fn set_once(&mut self, key: &str, value: Value) -> Result<()> {
-        let expr: path::Expression = key.parse()?;
+        let expr = self.parse_key(key)?;
+        self.typed.clear();
 
         // Traverse the cache using the path to (possibly) retrieve a value
         if let Some(ref mut val) = expr.get_mut(&mut self.cache) {
@@ -149,8 +368,22 @@ impl Config {
     }
 
     pub fn get<'de, T: Deserialize<'de>>(&self, key: &str) -> Result<T> {
+        self.get_with_separator(key, self.separator)
+    }
+
+    /// Get the value at `key`, whose segments are separated by `separator` rather than the
+    /// separator of this configuration.
+    ///
+    /// With a separator other than `.`, segments are taken literally, so a key containing
+    /// dots such as a domain name can be addressed: `sites/a.com` with `/` is the key
+    /// `a.com` of the table `sites`. Subscripts like `[0]` keep their meaning.
+    pub fn get_with_separator<'de, T: Deserialize<'de>>(
+        &self,
+        key: &str,
+        separator: char,
+    ) -> Result<T> {
         // Parse the key into a path expression
-        let expr: path::Expression = key.parse()?;
+        let expr = path::Expression::parse_with_separator(key, separator)?;
 
         // Traverse the cache using the path to (possibly) retrieve a value
         let value = expr.get(&self.cache).cloned();
@@ -158,17 +391,137 @@ impl Config {
         match value {
             Some(value) => {
                 // Deserialize the received value into the requested type
-                T::deserialize(value).map_err(|e| e.extend_with_key(key))
+                T::deserialize(ValueDeserializer::new(value, self.de_options.clone()))
+                    .map_err(|e| e.prepend_key(key.into()))
+            }
+
+            None => Err(ConfigError::NotFound(key.into())),
+        }
+    }
+
+    /// Get the value at `key`, computing a fallback with `default` if the key is absent.
+    ///
+    /// The closure is only invoked when the key cannot be found; any other error,
+    /// such as a value of the wrong type, is returned as is.
+    pub fn get_or_else<'de, T, F>(&self, key: &str, default: F) -> Result<T>
+    where
+        T: Deserialize<'de>,
+        F: FnOnce() -> T,
+    {
+        match self.get(key) {
+            Err(ConfigError::NotFound(_)) => Ok(default()),
+            result => result,
+        }
+    }
+
+    /// Get the value at `key` together with the origin of the source it came from.
+    ///
+    /// The origin is the URI reported by the source that provided the value, such as
+    /// a file path or `the environment`, and is `None` if that source has none.
+    pub fn get_with_origin<'de, T: Deserialize<'de>>(
+        &self,
+        key: &str,
+    ) -> Result<(T, Option<String>)> {
+        let expr = self.parse_key(key)?;
+
+        match expr.get(&self.cache).cloned() {
+            Some(value) => {
+                let origin = value.origin().map(String::from);
+                let value = T::deserialize(ValueDeserializer::new(value, self.de_options.clone()))
+                    .map_err(|e| e.prepend_key(key.into()))?;
+
+                Ok((value, origin))
             }
 
             None => Err(ConfigError::NotFound(key.into())),
         }
     }
 
+    /// Get a clone of the value at `key` as stored, without converting or deserializing it.
+    ///
+    /// Unlike `get::<Value>`, the kind and origin of the value are kept untouched, e.g. a
+    /// string is not parsed and a table keeps the origins of its entries. Deserializing the
+    /// returned value ignores the deserialization options of the builder, see
+    /// [`Value::try_into`].
+    pub fn get_raw(&self, key: &str) -> Result<Value> {
+        let expr = self.parse_key(key)?;
+
+        expr.get(&self.cache)
+            .cloned()
+            .ok_or_else(|| ConfigError::NotFound(key.into()))
+    }
+
+    /// Whether a value is present at `key`, whatever its type.
+    ///
+    /// A key explicitly set to null is present and returns `true`; a key that is not a
+    /// valid path returns `false`.
+    pub fn contains_key(&self, key: &str) -> bool {
+        match self.parse_key(key) {
+            Ok(expr) => expr.get(&self.cache).is_some(),
+            Err(_) => false,
+        }
+    }
+
+    /// Whether the value at `key` comes from the defaults, rather than from a source or an
+    /// override.
+    ///
+    /// Returns `false` when any source or override sets `key`, even to the same value as the
+    /// default, and for a key that is absent or not a valid path. For a table, any key of it
+    /// being set by a source or override is enough to return `false`. Values merged later
+    /// with [`apply_env`](Self::apply_env) are not taken into account.
+    pub fn is_default(&self, key: &str) -> bool {
+        let expr = match self.parse_key(key) {
+            Ok(expr) => expr,
+            Err(_) => return false,
+        };
+
+        let defaults = merge_layers(&self.defaults, &[], &Map::new(), &self.merge_options, None);
+        if expr.clone().get(&defaults).is_none() {
+            return false;
+        }
+
+        let explicit = merge_layers(
+            &Map::new(),
+            &self.layers,
+            &self.overrides,
+            &self.merge_options,
+            None,
+        );
+        match expr.get(&explicit) {
+            None => true,
+            // Deep defaults replace nil values
+            Some(value) => self.merge_options.deep_defaults && matches!(value.kind, ValueKind::Nil),
+        }
+    }
+
+    /// The comment written right above `key` in its source, without the comment markers.
+    ///
+    /// Comments are only collected from TOML files read
+    /// [`with_comments`](crate::File::with_comments); a comment of several lines is joined
+    /// with `\n`. When several sources set the key, the comment of the last one having one is
+    /// kept.
+    pub fn comment(&self, key: &str) -> Option<&str> {
+        let expr = self.parse_key(key).ok()?;
+
+        self.comments.get(&expr.to_string()).map(String::as_str)
+    }
+
     pub fn get_string(&self, key: &str) -> Result<String> {
         self.get(key).and_then(Value::into_string)
     }
 
+    /// Get the value at `key` rendered as a string, whatever its type.
+    ///
+    /// Unlike [`get_string`](Self::get_string) this never fails because of the type of the value:
+    /// scalars are rendered in their display form, nil as `nil`, and arrays and tables with
+    /// their [`Display`](std::fmt::Display) implementation.
+    /// Returns `None` only if the key is absent or is not a valid path.
+    pub fn get_string_lossy(&self, key: &str) -> Option<String> {
+        let expr = self.parse_key(key).ok()?;
+
+        expr.get(&self.cache).map(|value| value.to_string())
+    }
+
     pub fn get_int(&self, key: &str) -> Result<i64> {
         self.get(key).and_then(Value::into_int)
     }
@@ -178,15 +531,100 @@ impl Config {
     }
 
     pub fn get_bool(&self, key: &str) -> Result<bool> {
-        self.get(key).and_then(Value::into_bool)
+        self.get(key)
+    }
+
+    /// Get the duration at `key`, given as a number of seconds or as a string with a unit.
+    ///
+    /// Strings are a number followed by one of `ns`, `us`, `ms`, `s`, `m`, `h` or `d`,
+    /// like `"30s"` or `"1.5h"`. A string without unit is a number of seconds.
+    pub fn get_duration(&self, key: &str) -> Result<Duration> {
+        self.get::<Value>(key)
+            .and_then(units::into_duration)
+            .map_err(|e| e.extend_with_key(key))
+    }
+
+    /// Get the size in bytes at `key`, given as a number of bytes or as a string with a unit.
+    ///
+    /// Strings are a number followed by one of `B`, `KB`, `MB`, `GB`, `TB` (powers of 1000)
+    /// or `KiB`, `MiB`, `GiB`, `TiB` (powers of 1024), like `"10MB"`. Units are case-insensitive.
+    pub fn get_bytes(&self, key: &str) -> Result<u64> {
+        self.get::<Value>(key)
+            .and_then(units::into_bytes)
+            .map_err(|e| e.extend_with_key(key))
     }
 
     pub fn get_table(&self, key: &str) -> Result<Map<String, Value>> {
         self.get(key).and_then(Value::into_table)
     }
 
+    /// Get the names of the immediate children of the table at `key`, in sorted order.
+    ///
+    /// For `[feature.a]` and `[feature.b]`, `get_table_keys("feature")` returns `["a", "b"]`.
+    /// Fails with [`ConfigError::NotFound`] if the key is absent and with
+    /// [`ConfigError::Type`] if it is not a table.
+    pub fn get_table_keys(&self, key: &str) -> Result<Vec<String>> {
+        let table = self
+            .get::<Value>(key)?
+            .into_table()
+            .map_err(|e| e.prepend_key(key.into()))?;
+
+        let mut keys: Vec<String> = table.keys().cloned().collect();
+        keys.sort();
+        Ok(keys)
+    }
+
+    /// Get the elements of the array at `key`.
+    ///
+    /// Each element keeps its own origin, so an array extended by a later source, e.g. with a
+    /// key like `hosts[2]`, tells which source contributed which element.
     pub fn get_array(&self, key: &str) -> Result<Vec<Value>> {
-        self.get(key).and_then(Value::into_array)
+        self.get_raw(key).and_then(Value::into_array)
+    }
+
+    /// The root of the merged configuration, a table.
+    ///
+    /// This is read-only access to the values, with their origins; changing the configuration
+    /// goes through [`set`](Self::set) and the builder.
+    pub fn as_value(&self) -> &Value {
+        &self.cache
+    }
+
+    /// A new configuration whose root is the table at `key`, e.g. `database` to hand a module
+    /// only its own section.
+    ///
+    /// The subset is a snapshot: it has the values, comments and read options of this
+    /// configuration, but no sources, so refreshing it does not read them again.
+    ///
+    /// # Errors
+    ///
+    /// Fails with [`ConfigError::NotFound`] if the key is absent and with [`ConfigError::Type`]
+    /// if it is not a table.
+    pub fn subset(&self, key: &str) -> Result<Config> {
+        let expr = self.parse_key(key)?;
+        let value = expr
+            .clone()
+            .get(&self.cache)
+            .cloned()
+            .ok_or_else(|| ConfigError::NotFound(key.into()))?;
+
+        let origin = value.origin().map(String::from);
+        let table = value.into_table().map_err(|e| e.extend_with_key(key))?;
+
+        let prefix = format!("{}.", expr);
+        let comments = self
+            .comments
+            .iter()
+            .filter_map(|(key, comment)| {
+                key.strip_prefix(prefix.as_str())
+                    .map(|key| (key.to_string(), comment.clone()))
+            })
+            .collect();
+
+        Ok(Config::new(Value::new(origin.as_ref(), table))
+            .with_de_options(self.de_options.clone())
+            .with_separator(self.separator)
+            .with_comments(comments))
     }
 
     /// Attempt to deserialize the entire configuration into the requested type.
@@ -194,6 +632,73 @@ impl Config {
         T::deserialize(self)
     }
 
+    /// Attempt to deserialize the entire configuration into the requested type, taking the
+    /// keys it lacks from `base`.
+    ///
+    /// Values are looked up in this configuration first and in `base` for the keys absent
+    /// here, tables being layered key by key, so a field only set in `base` still
+    /// deserializes. Neither configuration is changed; the deserialization options of this
+    /// one apply.
+    pub fn try_deserialize_with_base<'de, T: Deserialize<'de>>(&self, base: &Config) -> Result<T> {
+        let mut layered = base.cache.clone();
+
+        if let ValueKind::Table(ref table) = self.cache.kind {
+            for (key, value) in table {
+                path::Expression::Identifier(key.clone()).set(&mut layered, value.clone());
+            }
+        }
+
+        T::deserialize(ValueDeserializer::new(layered, self.de_options.clone()))
+    }
+
+    /// Attempt to deserialize the section at `key` into the requested type.
+    ///
+    /// This is the same as [`get`](Self::get), spelled to pair with [`try_into`](Self::try_into):
+    /// only the subtree at `key` is deserialized. Fails with [`ConfigError::NotFound`] if the
+    /// key is absent, and with [`ConfigError::Type`] if it holds a scalar where a table
+    /// is expected.
+    pub fn try_deserialize_key<'de, T: Deserialize<'de>>(&self, key: &str) -> Result<T> {
+        self.get(key)
+    }
+
+    /// The entire configuration deserialized into `T`, deserialized by the first call and
+    /// shared by the next ones.
+    ///
+    /// Meant for hot paths reading the same struct repeatedly. Every method updating the
+    /// configuration, such as [`refresh_named`](Self::refresh_named) or
+    /// [`apply_env`](Self::apply_env), discards the cached values, so the next call
+    /// deserializes `T` again; changes made directly to [`cache`](Self::cache) do not. Clones
+    /// of the configuration start with no cached value. A failed deserialization is not
+    /// cached.
+    pub fn typed<T>(&self) -> Result<Arc<T>>
+    where
+        T: DeserializeOwned + Send + Sync + 'static,
+    {
+        if let Some(typed) = self.typed.get::<T>() {
+            return Ok(typed);
+        }
+
+        let typed: Arc<T> = Arc::new(T::deserialize(ValueDeserializer::new(
+            self.cache.clone(),
+            self.de_options.clone(),
+        ))?);
+        self.typed.insert(typed.clone());
+
+        Ok(typed)
+    }
+
+    /// Deserialize the entire configuration with `seed`, for types whose deserialization needs
+    /// some state known at runtime, such as a registry of plugins.
+    ///
+    /// The configuration is not consumed, and the deserialization options of the builder
+    /// apply like with [`try_into`](Self::try_into).
+    pub fn deserialize_seed<'de, S: DeserializeSeed<'de>>(&self, seed: S) -> Result<S::Value> {
+        seed.deserialize(ValueDeserializer::new(
+            self.cache.clone(),
+            self.de_options.clone(),
+        ))
+    }
+
     /// Attempt to serialize the entire configuration from the given type.
     pub fn try_from<T: Serialize>(from: &T) -> Result<Self> {
         let mut serializer = ConfigSerializer::default();
@@ -205,6 +710,199 @@ impl Config {
     pub fn deserialize<'de, T: Deserialize<'de>>(self) -> Result<T> {
         self.try_into()
     }
+
+    /// Flatten the configuration into a map from dotted keys to values rendered as strings.
+    ///
+    /// Nested tables produce keys such as `database.pool.size` and array elements keys such
+    /// as `servers[0].host`, both accepted by [`get`](Self::get). Scalars are rendered in
+    /// their display form and nil as `nil`; empty tables and arrays produce no key.
+    pub fn into_flat_string_map(self) -> Map<String, String> {
+        let mut flat = Map::new();
+        flatten_into(&mut flat, None, self.cache);
+        flat
+    }
+
+    /// A hash of the merged configuration, to cheaply tell whether it changed, e.g. after a
+    /// refresh.
+    ///
+    /// Only the values count: origins are ignored and tables are hashed in the sorted order of
+    /// their keys, so configurations with the same content have the same fingerprint whatever
+    /// their sources. The hash is FNV-1a, stable across runs and platforms, but it is not
+    /// cryptographic.
+    pub fn fingerprint(&self) -> u64 {
+        let mut hasher = Fnv(FNV_OFFSET_BASIS);
+        hash_value(&mut hasher, &self.cache);
+        hasher.0
+    }
+
+    /// Freeze the configuration into a [`SharedConfig`], which is cheap to clone.
+    pub fn into_shared(self) -> SharedConfig {
+        SharedConfig {
+            inner: Arc::new(self),
+        }
+    }
+}
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// 64-bit FNV-1a hasher, see [`Config::fingerprint`].
+struct Fnv(u64);
+
+impl Fnv {
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 = (self.0 ^ u64::from(byte)).wrapping_mul(FNV_PRIME);
+        }
+    }
+
+    /// Writes `s` prefixed with its length, so that consecutive strings cannot be confused.
+    fn write_str(&mut self, s: &str) {
+        self.write(&(s.len() as u64).to_le_bytes());
+        self.write(s.as_bytes());
+    }
+}
+
+/// Hashes the kind and content of `value`, with tables in the sorted order of their keys.
+fn hash_value(hasher: &mut Fnv, value: &Value) {
+    match value.kind {
+        ValueKind::Nil => hasher.write(&[0]),
+        ValueKind::Boolean(b) => hasher.write(&[1, b as u8]),
+        ValueKind::Integer(i) => {
+            hasher.write(&[2]);
+            hasher.write(&i.to_le_bytes());
+        }
+        ValueKind::Float(f) => {
+            hasher.write(&[3]);
+            hasher.write(&f.to_bits().to_le_bytes());
+        }
+        ValueKind::Number(ref n) => {
+            hasher.write(&[4]);
+            hasher.write_str(n);
+        }
+        ValueKind::String(ref s) => {
+            hasher.write(&[5]);
+            hasher.write_str(s);
+        }
+        ValueKind::Table(ref table) => {
+            let mut keys: Vec<&String> = table.keys().collect();
+            keys.sort();
+
+            hasher.write(&[6]);
+            hasher.write(&(keys.len() as u64).to_le_bytes());
+            for key in keys {
+                hasher.write_str(key);
+                hash_value(hasher, &table[key]);
+            }
+        }
+        ValueKind::Array(ref array) => {
+            hasher.write(&[7]);
+            hasher.write(&(array.len() as u64).to_le_bytes());
+            for element in array {
+                hash_value(hasher, element);
+            }
+        }
+    }
+}
+
+fn flatten_into(flat: &mut Map<String, String>, key: Option<String>, value: Value) {
+    match value.kind {
+        ValueKind::Table(table) => {
+            for (child, value) in table {
+                let child = match key {
+                    Some(ref key) => format!("{}.{}", key, child),
+                    None => child,
+                };
+                flatten_into(flat, Some(child), value);
+            }
+        }
+
+        ValueKind::Array(array) => {
+            for (index, value) in array.into_iter().enumerate() {
+                let child = format!("{}[{}]", key.as_deref().unwrap_or_default(), index);
+                flatten_into(flat, Some(child), value);
+            }
+        }
+
+        kind => {
+            flat.insert(key.unwrap_or_default(), kind.to_string());
+        }
+    }
+}
+
+/// Values deserialized from a [`Config`] by type, emptied when it changes.
+#[derive(Default)]
+struct TypedCache(Mutex<Map<TypeId, Arc<dyn Any + Send + Sync>>>);
+
+impl TypedCache {
+    fn get<T: Any + Send + Sync>(&self) -> Option<Arc<T>> {
+        let typed = self.0.lock().unwrap_or_else(|err| err.into_inner());
+
+        typed
+            .get(&TypeId::of::<T>())
+            .and_then(|value| value.clone().downcast().ok())
+    }
+
+    fn insert<T: Any + Send + Sync>(&self, value: Arc<T>) {
+        let mut typed = self.0.lock().unwrap_or_else(|err| err.into_inner());
+
+        typed.insert(TypeId::of::<T>(), value);
+    }
+
+    fn clear(&mut self) {
+        self.0
+            .get_mut()
+            .unwrap_or_else(|err| err.into_inner())
+            .clear();
+    }
+}
+
+// A clone may change independently, so it does not share the values
+impl Clone for TypedCache {
+    fn clone(&self) -> Self {
+        TypedCache::default()
+    }
+}
+
+impl fmt::Debug for TypedCache {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "TypedCache")
+    }
+}
+
+/// An immutable [`Config`] behind an [`Arc`], created with [`Config::into_shared`].
+///
+/// Cloning only increments a reference count, so it can be passed around threads and tasks
+/// freely. All the read accessors of [`Config`], such as [`get`](Config::get), are available
+/// through [`Deref`]; nothing can be set or refreshed.
+#[derive(Clone, Debug)]
+pub struct SharedConfig {
+    inner: Arc<Config>,
+}
+
+impl SharedConfig {
+    /// Attempt to deserialize the entire configuration into the requested type.
+    ///
+    /// Unlike [`Config::try_into`], this does not consume the configuration.
+    pub fn try_deserialize<'de, T: Deserialize<'de>>(&self) -> Result<T> {
+        T::deserialize(ValueDeserializer::new(
+            self.inner.cache.clone(),
+            self.inner.de_options.clone(),
+        ))
+    }
+
+    /// Returns `true` if both point to the same configuration.
+    pub fn ptr_eq(this: &SharedConfig, other: &SharedConfig) -> bool {
+        Arc::ptr_eq(&this.inner, &other.inner)
+    }
+}
+
+impl Deref for SharedConfig {
+    type Target = Config;
+
+    fn deref(&self) -> &Config {
+        &self.inner
+    }
 }
 
 impl Source for Config {
@@ -215,4 +913,11 @@ impl Source for Config {
     fn collect(&self) -> Result<Map<String, Value>> {
         self.cache.clone().into_table()
     }
+
+    fn known_keys(&self) -> Option<Vec<String>> {
+        match self.cache.kind {
+            ValueKind::Table(ref table) => Some(table.keys().cloned().collect()),
+            _ => Some(Vec::new()),
+        }
+    }
 }
@@ -1,14 +1,130 @@
 use std::collections::VecDeque;
+use std::convert::TryFrom;
 use std::iter::Enumerate;
+use std::sync::Arc;
 
 use serde::de;
 
 use crate::config::Config;
 use crate::error::*;
 use crate::map::Map;
-use crate::value::{Table, Value, ValueKind};
+use crate::value::{FloatPolicy, Table, Value, ValueKind};
+
+/// Deserialize an `Option<Option<T>>` field telling an absent key from an explicit null.
+///
+/// A nil value deserializes as `None` into an `Option<T>`, the same as an absent key, and
+/// serde cannot tell the outer and the inner level of a nested option apart on its own.
+/// Used together with `#[serde(default)]`, this helper turns an absent key into `None`,
+/// a nil value into `Some(None)` and any other value into `Some(Some(_))`:
+///
+/// ```rust
+/// # use serde_derive::Deserialize;
+/// #[derive(Deserialize)]
+/// struct Settings {
+///     #[serde(default, deserialize_with = "config::double_option")]
+///     proxy: Option<Option<String>>,
+/// }
+/// ```
+pub fn double_option<'de, D, T>(deserializer: D) -> std::result::Result<Option<Option<T>>, D::Error>
+where
+    D: de::Deserializer<'de>,
+    T: de::Deserialize<'de>,
+{
+    // Only reached for a present key
+    de::Deserialize::deserialize(deserializer).map(Some)
+}
+
+/// Options changing how values are deserialized, applying to a whole tree of values.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct DeOptions {
+    /// Decode strings deserialized as bytes from base64
+    pub(crate) bytes_as_base64: bool,
+
+    /// Whether non-finite floats are kept as floats
+    pub(crate) float_policy: FloatPolicy,
+
+    /// Strings accepted as booleans instead of the default ones, if set
+    pub(crate) bool_tokens: Option<Arc<BoolTokens>>,
+
+    /// Match enum variant names ignoring case
+    pub(crate) enum_case_insensitive: bool,
+}
+
+/// Strings deserializing to `true` and to `false`, in lowercase.
+#[derive(Debug)]
+pub(crate) struct BoolTokens {
+    pub(crate) truthy: Vec<String>,
+    pub(crate) falsy: Vec<String>,
+}
+
+impl BoolTokens {
+    fn parse(&self, value: Value) -> Result<bool> {
+        let token = match value.kind {
+            ValueKind::String(ref token) => token.to_lowercase(),
+            _ => return value.into_bool(),
+        };
+
+        if self.truthy.contains(&token) {
+            Ok(true)
+        } else if self.falsy.contains(&token) {
+            Ok(false)
+        } else {
+            Err(ConfigError::invalid_type(
+                value.origin().map(String::from),
+                Unexpected::Str(token),
+                "one of the configured boolean tokens",
+            ))
+        }
+    }
+}
+
+/// Deserializer of a [`Value`] and its children with the given options.
+pub(crate) struct ValueDeserializer {
+    value: Value,
+    options: DeOptions,
+}
+
+impl ValueDeserializer {
+    pub(crate) fn new(mut value: Value, options: DeOptions) -> Self {
+        // Rejected floats are seen as the string they were parsed from
+        if options.float_policy == FloatPolicy::Reject {
+            if let ValueKind::Float(f) = value.kind {
+                if !f.is_finite() {
+                    value.kind = ValueKind::String(f.to_string());
+                }
+            }
+        }
+
+        ValueDeserializer { value, options }
+    }
+
+    fn into_float(self) -> Result<f64> {
+        let origin = self.value.origin().map(String::from);
+        let f = self.value.into_float()?;
+
+        if self.options.float_policy == FloatPolicy::Reject && !f.is_finite() {
+            return Err(ConfigError::invalid_type(
+                origin,
+                Unexpected::Float(f),
+                "a finite floating point number",
+            ));
+        }
+
+        Ok(f)
+    }
+
+    /// Parses the literal of a [`ValueKind::Number`] into `T`.
+    fn parse_number<T: std::str::FromStr>(self, expected: &'static str) -> Result<T> {
+        let origin = self.value.origin().map(String::from);
+        let literal = self.value.into_string()?;
+
+        literal
+            .parse()
+            .map_err(|_| ConfigError::invalid_type(origin, Unexpected::Number(literal), expected))
+    }
+}
 
-impl<'de> de::Deserializer<'de> for Value {
+impl<'de> de::Deserializer<'de> for ValueDeserializer {
     type Error = ConfigError;
 
     #[inline]
@@ -17,72 +133,136 @@ impl<'de> de::Deserializer<'de> for Value {
         V: de::Visitor<'de>,
     {
         // Deserialize based on the underlying type
-        match self.kind {
+        match self.value.kind {
             ValueKind::Nil => visitor.visit_unit(),
             ValueKind::Integer(i) => visitor.visit_i64(i),
             ValueKind::Boolean(b) => visitor.visit_bool(b),
             ValueKind::Float(f) => visitor.visit_f64(f),
+            ValueKind::Number(n) => match n.parse() {
+                Ok(u) => visitor.visit_u64(u),
+                // Beyond any primitive, left to types parsing their value from a string
+                Err(_) => visitor.visit_string(n),
+            },
             ValueKind::String(s) => visitor.visit_string(s),
-            ValueKind::Array(values) => visitor.visit_seq(SeqAccess::new(values)),
-            ValueKind::Table(map) => visitor.visit_map(MapAccess::new(map)),
+            ValueKind::Array(values) => visitor.visit_seq(SeqAccess::new(values, self.options)),
+            ValueKind::Table(map) => visitor.visit_map(MapAccess::new(map, self.options)),
         }
     }
 
     #[inline]
     fn deserialize_bool<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
-        visitor.visit_bool(self.into_bool()?)
+        let value = match self.options.bool_tokens {
+            Some(ref tokens) => tokens.parse(self.value)?,
+            None => self.value.into_bool()?,
+        };
+
+        visitor.visit_bool(value)
     }
 
     #[inline]
     fn deserialize_i8<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
         // FIXME: This should *fail* if the value does not fit in the requets integer type
-        visitor.visit_i8(self.into_int()? as i8)
+        visitor.visit_i8(self.value.into_int()? as i8)
     }
 
     #[inline]
     fn deserialize_i16<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
         // FIXME: This should *fail* if the value does not fit in the requets integer type
-        visitor.visit_i16(self.into_int()? as i16)
+        visitor.visit_i16(self.value.into_int()? as i16)
     }
 
     #[inline]
     fn deserialize_i32<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
         // FIXME: This should *fail* if the value does not fit in the requets integer type
-        visitor.visit_i32(self.into_int()? as i32)
+        visitor.visit_i32(self.value.into_int()? as i32)
     }
 
     #[inline]
     fn deserialize_i64<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
-        visitor.visit_i64(self.into_int()?)
+        visitor.visit_i64(self.value.into_int()?)
     }
 
     #[inline]
     fn deserialize_u8<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
         // FIXME: This should *fail* if the value does not fit in the requets integer type
-        visitor.visit_u8(self.into_int()? as u8)
+        visitor.visit_u8(self.value.into_int()? as u8)
     }
 
     #[inline]
     fn deserialize_u16<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
         // FIXME: This should *fail* if the value does not fit in the requets integer type
-        visitor.visit_u16(self.into_int()? as u16)
+        visitor.visit_u16(self.value.into_int()? as u16)
     }
 
     #[inline]
     fn deserialize_u32<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
         // FIXME: This should *fail* if the value does not fit in the requets integer type
-        visitor.visit_u32(self.into_int()? as u32)
+        visitor.visit_u32(self.value.into_int()? as u32)
     }
 
     #[inline]
     fn deserialize_u64<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        if let ValueKind::Number(_) = self.value.kind {
+            return visitor.visit_u64(self.parse_number("an integer in the range of u64")?);
+        }
+
         // FIXME: This should *fail* if the value does not fit in the requets integer type
-        visitor.visit_u64(self.into_int()? as u64)
+        visitor.visit_u64(self.value.into_int()? as u64)
+    }
+
+    #[inline]
+    fn deserialize_i128<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        if let ValueKind::Number(_) = self.value.kind {
+            return visitor.visit_i128(self.parse_number("an integer in the range of i128")?);
+        }
+
+        visitor.visit_i128(self.value.into_int()?.into())
+    }
+
+    #[inline]
+    fn deserialize_u128<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        if let ValueKind::Number(_) = self.value.kind {
+            return visitor.visit_u128(self.parse_number("an integer in the range of u128")?);
+        }
+
+        let origin = self.value.origin().map(String::from);
+        let i = self.value.into_int()?;
+        match u128::try_from(i) {
+            Ok(u) => visitor.visit_u128(u),
+            Err(_) => Err(ConfigError::invalid_type(
+                origin,
+                Unexpected::Integer(i),
+                "an integer in the range of u128",
+            )),
+        }
     }
 
     #[inline]
     fn deserialize_f32<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
-        visitor.visit_f32(self.into_float()? as f32)
+        let origin = self.value.origin().map(String::from);
+        let integer = match self.value.kind {
+            ValueKind::Integer(i) => Some(i),
+            _ => None,
+        };
+
+        let f = self.into_float()?;
+        let narrowed = f as f32;
+
+        // Integers must be exact in an `f32`, other numbers within its range
+        let unexpected = match integer {
+            Some(i) if narrowed as i128 != i128::from(i) => Some(Unexpected::Integer(i)),
+            None if f.is_finite() && narrowed.is_infinite() => Some(Unexpected::Float(f)),
+            _ => None,
+        };
+        if let Some(unexpected) = unexpected {
+            return Err(ConfigError::invalid_type(
+                origin,
+                unexpected,
+                "a number representable as a 32-bit floating point",
+            ));
+        }
+
+        visitor.visit_f32(narrowed)
     }
 
     #[inline]
@@ -90,14 +270,19 @@ impl<'de> de::Deserializer<'de> for Value {
         visitor.visit_f64(self.into_float()?)
     }
 
+    #[inline]
+    fn deserialize_char<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_char(self.value.into_char()?)
+    }
+
     #[inline]
     fn deserialize_str<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
-        visitor.visit_string(self.into_string()?)
+        visitor.visit_string(self.value.into_string()?)
     }
 
     #[inline]
     fn deserialize_string<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
-        visitor.visit_string(self.into_string()?)
+        visitor.visit_string(self.value.into_string()?)
     }
 
     #[inline]
@@ -106,7 +291,7 @@ impl<'de> de::Deserializer<'de> for Value {
         V: de::Visitor<'de>,
     {
         // Match an explicit nil as None and everything else as Some
-        match self.kind {
+        match self.value.kind {
             ValueKind::Nil => visitor.visit_none(),
             _ => visitor.visit_some(self),
         }
@@ -119,6 +304,25 @@ impl<'de> de::Deserializer<'de> for Value {
         visitor.visit_newtype_struct(self)
     }
 
+    fn deserialize_struct<V>(
+        self,
+        name: &'static str,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        match self.value.kind {
+            // Structs may also be represented as a sequence of their fields
+            ValueKind::Array(values) => visitor.visit_seq(SeqAccess::new(values, self.options)),
+            _ => visitor.visit_map(MapAccess::new(
+                self.value.into_struct_table(name)?,
+                self.options,
+            )),
+        }
+    }
+
     fn deserialize_enum<V>(
         self,
         name: &'static str,
@@ -129,16 +333,56 @@ impl<'de> de::Deserializer<'de> for Value {
         V: de::Visitor<'de>,
     {
         visitor.visit_enum(EnumAccess {
-            value: self,
+            value: self.value,
             name,
             variants,
+            options: self.options,
         })
     }
 
+    fn deserialize_bytes<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        self.deserialize_byte_buf(visitor)
+    }
+
+    fn deserialize_byte_buf<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        let origin = self.value.origin().map(String::from);
+
+        match self.value.kind {
+            ValueKind::String(s) if self.options.bytes_as_base64 => match decode_base64(&s) {
+                Some(bytes) => visitor.visit_byte_buf(bytes),
+                None => Err(ConfigError::invalid_type(
+                    origin,
+                    Unexpected::Str(s),
+                    "base64-encoded bytes",
+                )),
+            },
+            ValueKind::String(s) => visitor.visit_byte_buf(s.into_bytes()),
+            kind => ValueDeserializer::new(Value::new(origin.as_ref(), kind), self.options)
+                .deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_unit<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        // An empty table is a unit as well, e.g. a marker section without keys
+        match self.value.kind {
+            ValueKind::Nil => visitor.visit_unit(),
+            ValueKind::Table(ref table) if table.is_empty() => visitor.visit_unit(),
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_unit_struct<V: de::Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value> {
+        self.deserialize_unit(visitor)
+    }
+
     forward_to_deserialize_any! {
-        char seq
-        bytes byte_buf map struct unit
-        identifier ignored_any unit_struct tuple_struct tuple
+        seq
+        map
+        identifier ignored_any tuple_struct tuple
     }
 }
 
@@ -161,12 +405,14 @@ impl<'de, 'a> de::Deserializer<'de> for StrDeserializer<'a> {
 
 struct SeqAccess {
     elements: Enumerate<::std::vec::IntoIter<Value>>,
+    options: DeOptions,
 }
 
 impl SeqAccess {
-    fn new(elements: Vec<Value>) -> Self {
+    fn new(elements: Vec<Value>, options: DeOptions) -> Self {
         SeqAccess {
             elements: elements.into_iter().enumerate(),
+            options,
         }
     }
 }
@@ -180,7 +426,7 @@ impl<'de> de::SeqAccess<'de> for SeqAccess {
     {
         match self.elements.next() {
             Some((idx, value)) => seed
-                .deserialize(value)
+                .deserialize(ValueDeserializer::new(value, self.options.clone()))
                 .map(Some)
                 .map_err(|e| e.prepend_index(idx)),
             None => Ok(None),
@@ -197,12 +443,21 @@ impl<'de> de::SeqAccess<'de> for SeqAccess {
 
 struct MapAccess {
     elements: VecDeque<(String, Value)>,
+    options: DeOptions,
 }
 
 impl MapAccess {
-    fn new(table: Map<String, Value>) -> Self {
+    fn new(table: Map<String, Value>, options: DeOptions) -> Self {
+        #[allow(unused_mut)]
+        let mut elements: Vec<(String, Value)> = table.into_iter().collect();
+
+        // Only an ordered map has a meaningful order, make the others deterministic
+        #[cfg(not(feature = "preserve_order"))]
+        elements.sort_by(|(a, _), (b, _)| a.cmp(b));
+
         MapAccess {
-            elements: table.into_iter().collect(),
+            elements: elements.into(),
+            options,
         }
     }
 }
@@ -215,8 +470,7 @@ impl<'de> de::MapAccess<'de> for MapAccess {
         K: de::DeserializeSeed<'de>,
     {
         if let Some(&(ref key_s, _)) = self.elements.front() {
-            let key_de = Value::new(None, key_s as &str);
-            let key = de::DeserializeSeed::deserialize(seed, key_de)?;
+            let key = de::DeserializeSeed::deserialize(seed, KeyDeserializer(key_s))?;
 
             Ok(Some(key))
         } else {
@@ -229,7 +483,8 @@ impl<'de> de::MapAccess<'de> for MapAccess {
         V: de::DeserializeSeed<'de>,
     {
         let (key, value) = self.elements.pop_front().unwrap();
-        de::DeserializeSeed::deserialize(seed, value).map_err(|e| e.prepend_key(key))
+        de::DeserializeSeed::deserialize(seed, ValueDeserializer::new(value, self.options.clone()))
+            .map_err(|e| e.prepend_key(key))
     }
 }
 
@@ -237,13 +492,20 @@ struct EnumAccess {
     value: Value,
     name: &'static str,
     variants: &'static [&'static str],
+    options: DeOptions,
 }
 
 impl EnumAccess {
     fn variant_deserializer(&self, name: &str) -> Result<StrDeserializer> {
-        self.variants
-            .iter()
-            .find(|&&s| s == name)
+        let mut variant = self.variants.iter().find(|&&s| s == name);
+
+        // An exact match wins over one differing only by case
+        if variant.is_none() && self.options.enum_case_insensitive {
+            let name = name.to_lowercase();
+            variant = self.variants.iter().find(|&&s| s.to_lowercase() == name);
+        }
+
+        variant
             .map(|&s| StrDeserializer(s))
             .ok_or_else(|| self.no_constructor_error(name))
     }
@@ -304,7 +566,10 @@ impl<'de> de::VariantAccess<'de> for EnumAccess {
         T: de::DeserializeSeed<'de>,
     {
         match self.value.kind {
-            ValueKind::Table(t) => seed.deserialize(t.into_iter().next().unwrap().1),
+            ValueKind::Table(t) => seed.deserialize(ValueDeserializer::new(
+                t.into_iter().next().unwrap().1,
+                self.options,
+            )),
             _ => unreachable!(),
         }
     }
@@ -314,9 +579,10 @@ impl<'de> de::VariantAccess<'de> for EnumAccess {
         V: de::Visitor<'de>,
     {
         match self.value.kind {
-            ValueKind::Table(t) => {
-                de::Deserializer::deserialize_seq(t.into_iter().next().unwrap().1, visitor)
-            }
+            ValueKind::Table(t) => de::Deserializer::deserialize_seq(
+                ValueDeserializer::new(t.into_iter().next().unwrap().1, self.options),
+                visitor,
+            ),
             _ => unreachable!(),
         }
     }
@@ -326,137 +592,204 @@ impl<'de> de::VariantAccess<'de> for EnumAccess {
         V: de::Visitor<'de>,
     {
         match self.value.kind {
-            ValueKind::Table(t) => {
-                de::Deserializer::deserialize_map(t.into_iter().next().unwrap().1, visitor)
-            }
+            ValueKind::Table(t) => de::Deserializer::deserialize_map(
+                ValueDeserializer::new(t.into_iter().next().unwrap().1, self.options),
+                visitor,
+            ),
             _ => unreachable!(),
         }
     }
 }
 
-impl<'de> de::Deserializer<'de> for Config {
-    type Error = ConfigError;
+/// Implements `Deserializer` for a type by converting it to a [`ValueDeserializer`].
+macro_rules! forward_to_value_deserializer {
+    ($ty:ty, $this:ident => $convert:expr) => {
+        impl<'de> de::Deserializer<'de> for $ty {
+            type Error = ConfigError;
+
+            forward_to_value_deserializer! {
+                @simple $this => $convert;
+                deserialize_any deserialize_bool deserialize_i8 deserialize_i16 deserialize_i32
+                deserialize_i64 deserialize_u8 deserialize_u16 deserialize_u32 deserialize_u64
+                deserialize_f32 deserialize_f64 deserialize_char deserialize_str
+                deserialize_string deserialize_bytes deserialize_byte_buf deserialize_option
+                deserialize_unit deserialize_seq deserialize_map deserialize_identifier
+                deserialize_ignored_any
+            }
 
-    #[inline]
-    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value>
-    where
-        V: de::Visitor<'de>,
-    {
-        // Deserialize based on the underlying type
-        match self.cache.kind {
-            ValueKind::Nil => visitor.visit_unit(),
-            ValueKind::Integer(i) => visitor.visit_i64(i),
-            ValueKind::Boolean(b) => visitor.visit_bool(b),
-            ValueKind::Float(f) => visitor.visit_f64(f),
-            ValueKind::String(s) => visitor.visit_string(s),
-            ValueKind::Array(values) => visitor.visit_seq(SeqAccess::new(values)),
-            ValueKind::Table(map) => visitor.visit_map(MapAccess::new(map)),
+            fn deserialize_unit_struct<V: de::Visitor<'de>>(
+                self,
+                name: &'static str,
+                visitor: V,
+            ) -> Result<V::Value> {
+                let $this = self;
+                $convert.deserialize_unit_struct(name, visitor)
+            }
+
+            fn deserialize_newtype_struct<V: de::Visitor<'de>>(
+                self,
+                name: &'static str,
+                visitor: V,
+            ) -> Result<V::Value> {
+                let $this = self;
+                $convert.deserialize_newtype_struct(name, visitor)
+            }
+
+            fn deserialize_tuple<V: de::Visitor<'de>>(
+                self,
+                len: usize,
+                visitor: V,
+            ) -> Result<V::Value> {
+                let $this = self;
+                $convert.deserialize_tuple(len, visitor)
+            }
+
+            fn deserialize_tuple_struct<V: de::Visitor<'de>>(
+                self,
+                name: &'static str,
+                len: usize,
+                visitor: V,
+            ) -> Result<V::Value> {
+                let $this = self;
+                $convert.deserialize_tuple_struct(name, len, visitor)
+            }
+
+            fn deserialize_struct<V: de::Visitor<'de>>(
+                self,
+                name: &'static str,
+                fields: &'static [&'static str],
+                visitor: V,
+            ) -> Result<V::Value> {
+                let $this = self;
+                $convert.deserialize_struct(name, fields, visitor)
+            }
+
+            fn deserialize_enum<V: de::Visitor<'de>>(
+                self,
+                name: &'static str,
+                variants: &'static [&'static str],
+                visitor: V,
+            ) -> Result<V::Value> {
+                let $this = self;
+                $convert.deserialize_enum(name, variants, visitor)
+            }
         }
-    }
+    };
+
+    (@simple $this:ident => $convert:expr; $($method:ident)*) => {
+        $(
+            #[inline]
+            fn $method<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+                let $this = self;
+                $convert.$method(visitor)
+            }
+        )*
+    };
+}
 
-    #[inline]
-    fn deserialize_bool<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
-        visitor.visit_bool(self.cache.into_bool()?)
-    }
+forward_to_value_deserializer!(Value, value => ValueDeserializer::new(value, DeOptions::default()));
 
-    #[inline]
-    fn deserialize_i8<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
-        // FIXME: This should *fail* if the value does not fit in the requets integer type
-        visitor.visit_i8(self.cache.into_int()? as i8)
-    }
+forward_to_value_deserializer!(Config, config => {
+    let options = config.de_options();
+    ValueDeserializer::new(config.cache, options)
+});
 
-    #[inline]
-    fn deserialize_i16<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
-        // FIXME: This should *fail* if the value does not fit in the requets integer type
-        visitor.visit_i16(self.cache.into_int()? as i16)
-    }
+/// Deserializer of a table key, borrowing it from the table instead of cloning it.
+///
+/// Keys are mostly matched against the fields of a struct, or copied once into a string by
+/// the visitor; other types, such as integer keys, parse it from a temporary value.
+struct KeyDeserializer<'a>(&'a str);
 
-    #[inline]
-    fn deserialize_i32<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
-        // FIXME: This should *fail* if the value does not fit in the requets integer type
-        visitor.visit_i32(self.cache.into_int()? as i32)
+impl<'a> KeyDeserializer<'a> {
+    fn value(&self) -> Value {
+        Value::new(None, self.0)
     }
+}
+
+impl<'de, 'a> de::Deserializer<'de> for KeyDeserializer<'a> {
+    type Error = ConfigError;
 
     #[inline]
-    fn deserialize_i64<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
-        visitor.visit_i64(self.cache.into_int()?)
+    fn deserialize_any<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_str(self.0)
     }
 
-    #[inline]
-    fn deserialize_u8<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
-        // FIXME: This should *fail* if the value does not fit in the requets integer type
-        visitor.visit_u8(self.cache.into_int()? as u8)
+    fn deserialize_option<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_some(self)
     }
 
-    #[inline]
-    fn deserialize_u16<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
-        // FIXME: This should *fail* if the value does not fit in the requets integer type
-        visitor.visit_u16(self.cache.into_int()? as u16)
+    fn deserialize_newtype_struct<V: de::Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value> {
+        visitor.visit_newtype_struct(self)
     }
 
-    #[inline]
-    fn deserialize_u32<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
-        // FIXME: This should *fail* if the value does not fit in the requets integer type
-        visitor.visit_u32(self.cache.into_int()? as u32)
+    fn deserialize_enum<V: de::Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        de::Deserializer::deserialize_enum(self.value(), name, variants, visitor)
     }
 
-    #[inline]
-    fn deserialize_u64<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
-        // FIXME: This should *fail* if the value does not fit in the requets integer type
-        visitor.visit_u64(self.cache.into_int()? as u64)
+    forward_to_value_deserializer! {
+        @simple key => key.value();
+        deserialize_bool deserialize_i8 deserialize_i16 deserialize_i32 deserialize_i64
+        deserialize_u8 deserialize_u16 deserialize_u32 deserialize_u64 deserialize_f32
+        deserialize_f64 deserialize_char deserialize_bytes deserialize_byte_buf
+        deserialize_unit deserialize_seq deserialize_map
     }
 
-    #[inline]
-    fn deserialize_f32<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
-        visitor.visit_f32(self.cache.into_float()? as f32)
+    forward_to_deserialize_any! {
+        str string identifier ignored_any unit_struct tuple tuple_struct struct
     }
+}
 
-    #[inline]
-    fn deserialize_f64<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
-        visitor.visit_f64(self.cache.into_float()?)
+/// Decodes standard base64, with or without padding, ignoring whitespace.
+fn decode_base64(s: &str) -> Option<Vec<u8>> {
+    fn sextet(c: u8) -> Option<u32> {
+        match c {
+            b'A'..=b'Z' => Some((c - b'A') as u32),
+            b'a'..=b'z' => Some((c - b'a') as u32 + 26),
+            b'0'..=b'9' => Some((c - b'0') as u32 + 52),
+            b'+' => Some(62),
+            b'/' => Some(63),
+            _ => None,
+        }
     }
 
-    #[inline]
-    fn deserialize_str<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
-        visitor.visit_string(self.cache.into_string()?)
+    let input: Vec<u8> = s.bytes().filter(|c| !c.is_ascii_whitespace()).collect();
+
+    // Padding is optional, but if present it must complete the last group
+    let padding = if input.ends_with(b"==") {
+        2
+    } else if input.ends_with(b"=") {
+        1
+    } else {
+        0
+    };
+    let data = &input[..input.len() - padding];
+    if padding > 0 && padding != (4 - data.len() % 4) % 4 {
+        return None;
     }
 
-    #[inline]
-    fn deserialize_string<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
-        visitor.visit_string(self.cache.into_string()?)
+    if data.len() % 4 == 1 {
+        return None;
     }
 
-    #[inline]
-    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value>
-    where
-        V: de::Visitor<'de>,
-    {
-        // Match an explicit nil as None and everything else as Some
-        match self.cache.kind {
-            ValueKind::Nil => visitor.visit_none(),
-            _ => visitor.visit_some(self),
+    let mut bytes = Vec::with_capacity(data.len() * 3 / 4);
+    for chunk in data.chunks(4) {
+        let mut group = 0;
+        for (i, &c) in chunk.iter().enumerate() {
+            group |= sextet(c)? << (18 - 6 * i);
         }
-    }
 
-    fn deserialize_enum<V>(
-        self,
-        name: &'static str,
-        variants: &'static [&'static str],
-        visitor: V,
-    ) -> Result<V::Value>
-    where
-        V: de::Visitor<'de>,
-    {
-        visitor.visit_enum(EnumAccess {
-            value: self.cache,
-            name,
-            variants,
-        })
+        let group = group.to_be_bytes();
+        bytes.extend_from_slice(&group[1..chunk.len()]);
     }
 
-    forward_to_deserialize_any! {
-        char seq
-        bytes byte_buf map struct unit newtype_struct
-        identifier ignored_any unit_struct tuple_struct tuple
-    }
+    Some(bytes)
 }
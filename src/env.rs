@@ -1,9 +1,13 @@
 use std::env;
+use std::fs;
 
+use serde::de::DeserializeOwned;
+
+use crate::case::KeyCase;
 use crate::error::*;
 use crate::map::Map;
 use crate::source::Source;
-use crate::value::{Value, ValueKind};
+use crate::value::{FloatPolicy, Value, ValueKind};
 
 #[derive(Clone, Debug)]
 pub struct Environment {
@@ -24,8 +28,69 @@ pub struct Environment {
     /// Ignore empty env values (treat as unset).
     ignore_empty: bool,
 
+    /// Value marking variables to skip, keeping the value of lower sources.
+    inherit_token: Option<String>,
+
     /// Parses booleans, integers and floats if they're detected (can be safely parsed).
     try_parsing: bool,
+
+    /// Fail instead of picking a winner when several variables map to the same key.
+    detect_collisions: bool,
+
+    /// Alternate source for the environment, used instead of the process environment.
+    source: Option<Map<String, String>>,
+
+    /// Read the value of keys ending with `file_suffix` from the file they point to.
+    file_indirection: bool,
+
+    /// Suffix marking keys whose value is a path, `_FILE` by default.
+    file_suffix: String,
+
+    /// Remove trailing newlines from the contents of indirected files.
+    trim_file_newline: bool,
+
+    /// Only keep keys containing this token, checked case-insensitively after the prefix is removed.
+    contains_filter: Option<String>,
+
+    /// Keys that must be set by some variable, see [`Environment::bind`].
+    required: Vec<String>,
+
+    /// Only keep these exact keys, if set.
+    allowlist: Option<Vec<String>>,
+
+    /// Convert keys to lowercase, `true` by default.
+    lowercase: bool,
+
+    /// Key set by a variable named exactly like the prefix.
+    prefix_key: Option<String>,
+
+    /// Number of leading key segments to drop.
+    leading_segments: usize,
+
+    /// Keep the prefix in the keys of the variables it selects.
+    keep_prefix: bool,
+
+    /// Whether `try_parsing` turns `inf` and `nan` into floats.
+    float_policy: FloatPolicy,
+
+    /// Keep numbers with leading zeros as strings when parsing.
+    strict_int_parse: bool,
+
+    /// Digit grouping characters ignored when parsing numbers.
+    numeric_separators: Vec<char>,
+
+    /// Key under which all the collected keys are nested.
+    nest_under: Option<String>,
+
+    /// Separator splitting values into lists.
+    list_separator: Option<String>,
+
+    /// Only split the values of these keys into lists, if set.
+    list_parse_keys: Option<Vec<String>>,
+
+    /// Keys whose values are parsed as JSON arrays.
+    #[cfg(feature = "json")]
+    json_array_keys: Vec<String>,
 }
 
 impl Environment {
@@ -40,11 +105,183 @@ impl Environment {
         }
     }
 
+    /// An environment for the fields of the struct `T`, failing to collect if a variable
+    /// is missing for one of its required fields.
+    ///
+    /// Fields are required unless they are an `Option` or have a `#[serde(default)]`.
+    /// Without a `prefix`, the name of the struct in `SCREAMING_SNAKE_CASE` is used,
+    /// so `AppConfig` reads variables such as `APP_CONFIG_PORT`.
+    ///
+    /// Only top-level fields are checked; one holding a nested struct is considered
+    /// set if any variable maps to a key below it.
+    ///
+    /// # Errors
+    ///
+    /// Fails if `T` does not deserialize from a struct.
+    pub fn bind<T: DeserializeOwned>(prefix: Option<&str>) -> Result<Self> {
+        let prefix = match prefix {
+            Some(prefix) => prefix.to_string(),
+            None => KeyCase::CamelToSnake
+                .convert(crate::schema::struct_fields::<T>()?.0)
+                .to_uppercase(),
+        };
+
+        Ok(Environment {
+            required: crate::schema::required_fields::<T>()?
+                .into_iter()
+                .map(|names| names[0].to_lowercase())
+                .collect(),
+            ..Environment::with_prefix(&prefix)
+        })
+    }
+
+    /// Keys required by [`bind`](Self::bind) and [`require_keys`](Self::require_keys).
+    pub fn required_keys(&self) -> &[String] {
+        &self.required
+    }
+
+    /// Fail to collect unless some variable sets each of `keys`, or a key nested in it.
+    ///
+    /// Keys are compared ignoring case, and missing ones are reported by the name of their
+    /// variable.
+    pub fn require_keys(mut self, keys: &[&str]) -> Self {
+        self.required
+            .extend(keys.iter().map(|key| key.to_lowercase()));
+        self
+    }
+
+    /// Only keep the variables whose key is exactly one of `keys`, skipping any other.
+    ///
+    /// Keys are matched once fully normalized, e.g. `database.url` for `APP__DATABASE__URL` with
+    /// a prefix of `APP` and a separator of `__`. Skipped variables are neither read through
+    /// [`file_indirection`](Self::file_indirection) nor parsed. Together with
+    /// [`require_keys`](Self::require_keys), the environment must then provide exactly the
+    /// expected keys.
+    pub fn allowlist(mut self, keys: &[&str]) -> Self {
+        self.allowlist = Some(keys.iter().map(|key| key.to_string()).collect());
+        self
+    }
+
     pub fn prefix(mut self, s: &str) -> Self {
         self.prefix = Some(s.into());
         self
     }
 
+    /// Nest all the collected keys under `key`, e.g. `env` or `sources.env`, instead of the root
+    /// of the configuration, so that `APP_PORT` with a prefix of `APP` becomes `env.port`.
+    ///
+    /// The nesting wraps the keys once they are complete, after the separator nests them
+    /// further, so `APP_DATABASE__URL` with a separator of `__` becomes `env.database.url`.
+    /// [`allowlist`](Self::allowlist) and [`require_keys`](Self::require_keys) still refer to
+    /// the keys without it.
+    pub fn nest_under(mut self, key: &str) -> Self {
+        self.nest_under = Some(key.into());
+        self
+    }
+
+    /// Keep the prefix at the start of the keys instead of removing it.
+    ///
+    /// Only variables with the prefix are still collected, but with a prefix of `APP` and a
+    /// separator of `_`, `APP_DEBUG` becomes `app.debug` rather than `debug`.
+    pub fn keep_prefix(mut self, keep: bool) -> Self {
+        self.keep_prefix = keep;
+        self
+    }
+
+    /// Map the variable named exactly like the prefix, e.g. `APP` for a prefix of `APP`,
+    /// to `key`, which is used as is.
+    ///
+    /// By default, or with `None`, that variable is skipped like any other variable not
+    /// starting with the prefix and separator.
+    pub fn prefix_as_key(mut self, key: Option<&str>) -> Self {
+        self.prefix_key = key.map(String::from);
+        self
+    }
+
+    /// Drop the first `n` segments of each key, whatever they are.
+    ///
+    /// Segments are dropped after the prefix is removed and the separator replaced, so with a
+    /// separator of `_` and `n = 1`, `APPNAME_DATABASE_URL` becomes `database.url`.
+    /// Variables with no segment left are skipped.
+    pub fn drop_leading_segments(mut self, n: usize) -> Self {
+        self.leading_segments = n;
+        self
+    }
+
+    /// Keep values with leading zeros, like `007`, as strings with
+    /// [`try_parsing`](Self::try_parsing), instead of parsing them as numbers.
+    ///
+    /// A lone `0`, or a zero before the decimal point like `0.5`, is still a number.
+    pub fn strict_int_parse(mut self, strict: bool) -> Self {
+        self.strict_int_parse = strict;
+        self
+    }
+
+    /// Ignore the digit grouping `separators` when [`try_parsing`](Self::try_parsing) parses
+    /// numbers, so that with `&['_', ',']` both `1_000` and `1,000` become the integer `1000`.
+    ///
+    /// The characters are removed anywhere in the value before trying to parse it as an integer
+    /// or a float, so `1,5` becomes `15`; values that are still not numbers, like `a,b`, are kept
+    /// unchanged as strings. Values split by [`list_separator`](Self::list_separator) are
+    /// split first, so a separator listed in both only splits lists. None by default.
+    pub fn numeric_separators(mut self, separators: &[char]) -> Self {
+        self.numeric_separators = separators.to_vec();
+        self
+    }
+
+    /// Split values into arrays on `separator`, e.g. `a,b,c` into `["a", "b", "c"]` with `,`.
+    ///
+    /// Every value is split, unless some keys are given with
+    /// [`with_list_parse_key`](Self::with_list_parse_key) or
+    /// [`list_keys_from_schema`](Self::list_keys_from_schema), in which case only theirs are.
+    /// An empty value becomes an empty array, and each element is parsed on its own by
+    /// [`try_parsing`](Self::try_parsing).
+    pub fn list_separator(mut self, separator: &str) -> Self {
+        self.list_separator = Some(separator.into());
+        self
+    }
+
+    /// Only split the value of `key` into an array, along with the other keys given this way.
+    ///
+    /// Keys are matched once fully normalized, like for [`allowlist`](Self::allowlist), and
+    /// nothing is split unless a [`list_separator`](Self::list_separator) is set.
+    pub fn with_list_parse_key(mut self, key: &str) -> Self {
+        self.list_parse_keys
+            .get_or_insert_with(Vec::new)
+            .push(key.into());
+        self
+    }
+
+    /// Split the values of all the `keys` into arrays, e.g. the dotted paths of the array
+    /// fields of the target struct, as many calls to
+    /// [`with_list_parse_key`](Self::with_list_parse_key) would.
+    ///
+    /// A [`list_separator`](Self::list_separator) must still be set; other keys stay scalars.
+    pub fn list_keys_from_schema(self, keys: &[&str]) -> Self {
+        keys.iter()
+            .fold(self, |env, key| env.with_list_parse_key(key))
+    }
+
+    /// Parse the value of `key` as a JSON array, e.g. `[8080, 8081]` or `["a", "b"]`, keeping
+    /// the types of its elements.
+    ///
+    /// Keys are matched once fully normalized, like for [`allowlist`](Self::allowlist), so that
+    /// other values starting with `[` are left alone. Collecting fails if the value of `key` is
+    /// not a valid JSON array. It takes precedence over [`list_separator`](Self::list_separator)
+    /// and [`try_parsing`](Self::try_parsing).
+    #[cfg(feature = "json")]
+    pub fn with_json_array_key(mut self, key: &str) -> Self {
+        self.json_array_keys.push(key.into());
+        self
+    }
+
+    /// Set whether [`try_parsing`](Self::try_parsing) turns values such as `inf` and `nan` into
+    /// floats, which it does by default. With [`FloatPolicy::Reject`] they stay strings.
+    pub fn float_policy(mut self, policy: FloatPolicy) -> Self {
+        self.float_policy = policy;
+        self
+    }
+
     pub fn separator(mut self, s: &str) -> Self {
         self.separator = Some(s.into());
         self
@@ -55,12 +292,125 @@ impl Environment {
         self
     }
 
+    /// Skip the variables whose value is exactly `token`, e.g. `__inherit__`, so that they do not
+    /// override the value set by lower sources.
+    ///
+    /// Useful with deployment templates setting every variable. The value is compared before
+    /// being read through [`file_indirection`](Self::file_indirection) or parsed, and the
+    /// variable is skipped entirely, which also means it does not satisfy
+    /// [`require_keys`](Self::require_keys).
+    pub fn inherit_token(mut self, token: &str) -> Self {
+        self.inherit_token = Some(token.into());
+        self
+    }
+
     /// Note: enabling `try_parsing` can reduce performance it will try and parse
     /// each environment variable 3 times (bool, i64, f64)
     pub fn try_parsing(mut self, try_parsing: bool) -> Self {
         self.try_parsing = try_parsing;
         self
     }
+
+    /// Keep the case of variable names in the keys, so that `MY_Value` maps to `MY_Value`
+    /// instead of `my_value`.
+    ///
+    /// The prefix, [`contains_filter`](Self::contains_filter) and file suffix are still
+    /// matched ignoring case.
+    pub fn without_lowercasing(mut self) -> Self {
+        self.lowercase = false;
+        self
+    }
+
+    /// Fail to collect if several variables map to the same key, e.g. `Path` and `PATH`.
+    ///
+    /// By default variables are processed in sorted order of their names and the last one wins.
+    pub fn detect_collisions(mut self, detect: bool) -> Self {
+        self.detect_collisions = detect;
+        self
+    }
+
+    /// Use `source` as the environment instead of the variables of the process.
+    ///
+    /// Mostly useful for testing. Passing `None` restores the default.
+    pub fn source(mut self, source: Option<Map<String, String>>) -> Self {
+        self.source = source;
+        self
+    }
+
+    /// Treat the value of variables ending with the file suffix as the path of a file
+    /// to read the actual value from, as commonly done for secrets.
+    ///
+    /// With a prefix of `APP` and a separator of `_`, `APP_DB_PASSWORD_FILE=/run/secrets/db`
+    /// sets `db.password` to the contents of `/run/secrets/db`.
+    /// Collecting fails if the file cannot be read.
+    pub fn file_indirection(mut self, enabled: bool) -> Self {
+        self.file_indirection = enabled;
+        self
+    }
+
+    /// Only keep variables whose key contains `token`, ignoring case.
+    ///
+    /// The check is done on the key once the prefix is removed and before the separator is
+    /// replaced, so with a prefix of `APP` the token `_SECRET_` keeps `APP_DB_SECRET_KEY` but not
+    /// `APP_SECRET_KEY`. Skipped variables are neither read through
+    /// [`file_indirection`](Self::file_indirection) nor parsed with [`try_parsing`](Self::try_parsing).
+    pub fn contains_filter(mut self, token: &str) -> Self {
+        self.contains_filter = Some(token.to_lowercase());
+        self
+    }
+
+    /// Suffix marking variables that point to a file, `_FILE` by default.
+    pub fn file_suffix(mut self, suffix: &str) -> Self {
+        self.file_suffix = suffix.into();
+        self
+    }
+
+    /// Whether trailing newlines are removed from the contents of files, `true` by default.
+    pub fn trim_file_newline(mut self, trim: bool) -> Self {
+        self.trim_file_newline = trim;
+        self
+    }
+
+    /// Combine this environment with `other` into a single source.
+    ///
+    /// Both are collected and merged, with `other` taking precedence when
+    /// they produce the same key.
+    pub fn chain(self, other: Environment) -> EnvironmentChain {
+        EnvironmentChain {
+            environments: vec![self, other],
+        }
+    }
+}
+
+/// Several [`Environment`]s collected as one source, see [`Environment::chain`].
+#[derive(Clone, Debug)]
+pub struct EnvironmentChain {
+    environments: Vec<Environment>,
+}
+
+impl EnvironmentChain {
+    /// Append another environment, which takes precedence over all previous ones.
+    pub fn chain(mut self, other: Environment) -> Self {
+        self.environments.push(other);
+        self
+    }
+}
+
+impl Environment {
+    fn read_indirect(&self, name: &str, path: &str) -> Result<String> {
+        let contents = fs::read_to_string(path).map_err(|err| {
+            ConfigError::Message(format!(
+                "could not read file {:?} from environment variable {:?}: {}",
+                path, name, err
+            ))
+        })?;
+
+        Ok(if self.trim_file_newline {
+            contents.trim_end_matches(&['\r', '\n'][..]).to_string()
+        } else {
+            contents
+        })
+    }
 }
 
 impl Default for Environment {
@@ -69,22 +419,140 @@ impl Default for Environment {
             prefix: None,
             separator: None,
             ignore_empty: false,
+            inherit_token: None,
             try_parsing: false,
+            detect_collisions: false,
+            source: None,
+            file_indirection: false,
+            file_suffix: "_FILE".into(),
+            trim_file_newline: true,
+            contains_filter: None,
+            required: Vec::new(),
+            allowlist: None,
+            lowercase: true,
+            prefix_key: None,
+            leading_segments: 0,
+            keep_prefix: false,
+            float_policy: FloatPolicy::default(),
+            strict_int_parse: false,
+            numeric_separators: Vec::new(),
+            nest_under: None,
+            list_separator: None,
+            list_parse_keys: None,
+            #[cfg(feature = "json")]
+            json_array_keys: Vec::new(),
         }
     }
 }
 
-impl Source for Environment {
-    fn clone_into_box(&self) -> Box<dyn Source + Send + Sync> {
-        Box::new((*self).clone())
+impl Environment {
+    /// Collect the selected variables as [`collect`](Source::collect) does, but with their
+    /// values as strings, before [`try_parsing`](Self::try_parsing) applies.
+    ///
+    /// Useful to log or audit what the environment literally contained. Values read through
+    /// [`file_indirection`](Self::file_indirection) are the contents of the files.
+    pub fn collect_raw(&self) -> Result<Map<String, String>> {
+        let mut m = Map::new();
+
+        let rules = self.rules();
+
+        // Names of the variables that produced each key, to report collisions
+        let mut origins = Map::new();
+
+        for (name, value) in self.sorted_vars() {
+            let (key, value) = match self.select(&rules, &name, value)? {
+                Some(selected) => selected,
+                None => continue,
+            };
+
+            if self.detect_collisions {
+                if let Some(previous) = origins.insert(key.clone(), name.clone()) {
+                    return Err(ConfigError::Message(format!(
+                        "environment variables {:?} and {:?} both map to key {:?}",
+                        previous, name, key
+                    )));
+                }
+            }
+
+            m.insert(key, value);
+        }
+
+        let missing: Vec<String> = self
+            .required
+            .iter()
+            .filter(|&required| {
+                let nested = format!("{}.", required);
+                !m.keys()
+                    .map(|key| key.to_lowercase())
+                    .any(|key| key == *required || key.starts_with(&nested))
+            })
+            .map(|required| {
+                let required = if rules.separator.is_empty() {
+                    required.clone()
+                } else {
+                    required.replace('.', &rules.separator)
+                };
+                let name = match self.prefix {
+                    Some(ref prefix) => format!("{}{}{}", prefix, rules.group_separator, required),
+                    None => required,
+                };
+                format!("{:?}", name.to_uppercase())
+            })
+            .collect();
+
+        if !missing.is_empty() {
+            return Err(ConfigError::Message(format!(
+                "missing environment variables for required fields: {}",
+                missing.join(", ")
+            )));
+        }
+
+        if self.nest_under.is_some() {
+            m = m
+                .into_iter()
+                .map(|(key, value)| (self.nest(key), value))
+                .collect();
+        }
+
+        Ok(m)
     }
 
-    fn collect(&self) -> Result<Map<String, Value>> {
-        let mut m = Map::new();
+    /// Iterate lazily over the selected variables, yielding the same keys and values as
+    /// [`collect`](Source::collect) would.
+    ///
+    /// Each variable is only processed when the iterator reaches it, which avoids building
+    /// a map when the variables are folded or filtered. Checks that need all the variables
+    /// are skipped: neither [`require_keys`](Self::require_keys) nor
+    /// [`detect_collisions`](Self::detect_collisions) apply. Keys colliding after lowercasing
+    /// are all yielded, the one `collect` keeps coming last.
+    ///
+    /// A variable that would make `collect` fail yields an error instead, such as one whose
+    /// [`file_indirection`](Self::file_indirection) file cannot be read or whose value cannot
+    /// be parsed; the iterator can go on with the next variables.
+    pub fn iter(&self) -> impl Iterator<Item = Result<(String, Value)>> + '_ {
+        let rules = self.rules();
         let uri: String = "the environment".into();
 
-        let separator = self.separator.as_deref().unwrap_or("");
-        let group_separator = self.separator.as_deref().unwrap_or("_");
+        self.sorted_vars()
+            .into_iter()
+            .filter_map(move |(name, value)| {
+                let (key, value) = match self.select(&rules, &name, value) {
+                    Ok(Some(selected)) => selected,
+                    Ok(None) => return None,
+                    Err(error) => return Some(Err(error)),
+                };
+
+                Some(
+                    self.to_value(&uri, &key, value)
+                        .map(|value| (self.nest(key), value)),
+                )
+            })
+    }
+
+    /// Separators and affixes derived from the settings, shared by all the variables.
+    fn rules(&self) -> Rules {
+        let separator = self.separator.clone().unwrap_or_default();
+        let group_separator = self.separator.clone().unwrap_or_else(|| "_".into());
 
         // Define a prefix pattern to test and exclude from keys
         let prefix_pattern = self
@@ -92,46 +560,321 @@ impl Source for Environment {
             .as_ref()
             .map(|prefix| format!("{}{}", prefix, group_separator).to_lowercase());
 
-        for (key, value) in env::vars() {
-            // Treat empty environment variables as unset
-            if self.ignore_empty && value.is_empty() {
-                continue;
+        Rules {
+            separator,
+            group_separator,
+            prefix_pattern,
+            file_suffix: self.file_suffix.to_lowercase(),
+        }
+    }
+
+    /// The variables to read, sorted so the winner of keys colliding after lowercasing is
+    /// deterministic.
+    fn sorted_vars(&self) -> Vec<(String, String)> {
+        let mut vars: Vec<(String, String)> = match self.source {
+            Some(ref source) => source.clone().into_iter().collect(),
+            None => env::vars().collect(),
+        };
+        vars.sort();
+        vars
+    }
+
+    /// The key and raw value of the variable `name`, or `None` if it is not selected.
+    ///
+    /// Fails if the value is read from a file that cannot be read.
+    fn select(&self, rules: &Rules, name: &str, value: String) -> Result<Option<(String, String)>> {
+        // Treat empty environment variables as unset
+        if self.ignore_empty && value.is_empty() {
+            return Ok(None);
+        }
+
+        // Keep whatever lower sources set
+        if self.inherit_token.as_ref() == Some(&value) {
+            return Ok(None);
+        }
+
+        // A variable named like the prefix itself maps to `prefix_key`, if any
+        let bare_key = match (&self.prefix, &self.prefix_key) {
+            (Some(prefix), Some(prefix_key)) if name.to_lowercase() == prefix.to_lowercase() => {
+                Some(prefix_key.clone())
             }
+            _ => None,
+        };
+
+        let (key, indirect) = match bare_key {
+            Some(key) => (key, false),
+            None => {
+                let mut key = if self.lowercase {
+                    name.to_lowercase()
+                } else {
+                    name.to_string()
+                };
 
-            let mut key = key.to_lowercase();
+                // Check for prefix
+                if let Some(ref prefix_pattern) = rules.prefix_pattern {
+                    match strip_prefix_ignore_case(&key, prefix_pattern) {
+                        // Never insert an empty key, e.g. for `APP_`
+                        Some("") => return Ok(None),
 
-            // Check for prefix
-            if let Some(ref prefix_pattern) = prefix_pattern {
-                if key.starts_with(prefix_pattern) {
-                    // Remove this prefix from the key
-                    key = key[prefix_pattern.len()..].to_string();
+                        // Remove this prefix from the key, unless it should be kept
+                        Some(rest) if !self.keep_prefix => key = rest.to_string(),
+                        Some(_) => {}
+
+                        // Skip this key, including one named like the bare prefix
+                        None => return Ok(None),
+                    }
+                }
+
+                // Skip keys without the filtered token
+                if let Some(ref token) = self.contains_filter {
+                    if !key.to_lowercase().contains(token.as_str()) {
+                        return Ok(None);
+                    }
+                }
+
+                // The value is read from the file it points to, once the key is kept
+                let indirect = if self.file_indirection {
+                    strip_suffix_ignore_case(&key, &rules.file_suffix).map(str::len)
                 } else {
-                    // Skip this key
-                    continue;
+                    None
+                };
+                if let Some(len) = indirect {
+                    key.truncate(len);
+                }
+
+                // If separator is given replace with `.`
+                if !rules.separator.is_empty() {
+                    key = key.replace(&rules.separator, ".");
                 }
+
+                // Drop the leading segments, skipping keys that have no more
+                if self.leading_segments > 0 {
+                    match key
+                        .splitn(self.leading_segments + 1, '.')
+                        .nth(self.leading_segments)
+                    {
+                        Some(rest) if !rest.is_empty() => key = rest.to_string(),
+                        _ => return Ok(None),
+                    }
+                }
+
+                (key, indirect.is_some())
             }
+        };
 
-            // If separator is given replace with `.`
-            if !separator.is_empty() {
-                key = key.replace(separator, ".");
+        if let Some(ref allowlist) = self.allowlist {
+            if !allowlist.contains(&key) {
+                return Ok(None);
             }
+        }
 
-            let value = if self.try_parsing {
-                // convert to lowercase because bool parsing expects all lowercase
-                if let Ok(parsed) = value.to_lowercase().parse::<bool>() {
-                    ValueKind::Boolean(parsed)
-                } else if let Ok(parsed) = value.parse::<i64>() {
-                    ValueKind::Integer(parsed)
-                } else if let Ok(parsed) = value.parse::<f64>() {
-                    ValueKind::Float(parsed)
-                } else {
-                    ValueKind::String(value)
-                }
-            } else {
-                ValueKind::String(value)
-            };
+        let value = if indirect {
+            self.read_indirect(name, &value)?
+        } else {
+            value
+        };
+
+        Ok(Some((key, value)))
+    }
+
+    /// `key` nested under [`nest_under`](Self::nest_under), if set.
+    fn nest(&self, key: String) -> String {
+        match self.nest_under {
+            Some(ref root) => format!("{}.{}", root, key),
+            None => key,
+        }
+    }
+
+    /// The value of `key`, before nesting, split into an array if it is a list.
+    fn to_value(&self, uri: &String, key: &str, value: String) -> Result<Value> {
+        #[cfg(feature = "json")]
+        {
+            if self.json_array_keys.iter().any(|json_key| json_key == key) {
+                return parse_json_array(uri, key, &value);
+            }
+        }
+
+        let separator = match self.list_separator {
+            Some(ref separator) => separator,
+            None => return Ok(Value::new(Some(uri), self.parse_value(value))),
+        };
+
+        let is_list = match self.list_parse_keys {
+            Some(ref keys) => keys.iter().any(|list_key| list_key == key),
+            None => true,
+        };
+        if !is_list {
+            return Ok(Value::new(Some(uri), self.parse_value(value)));
+        }
+
+        let elements: Vec<Value> = if value.is_empty() {
+            Vec::new()
+        } else {
+            value
+                .split(separator.as_str())
+                .map(|element| Value::new(Some(uri), self.parse_value(element.into())))
+                .collect()
+        };
+
+        Ok(Value::new(Some(uri), elements))
+    }
+
+    fn parse_value(&self, value: String) -> ValueKind {
+        if !self.try_parsing {
+            return ValueKind::String(value);
+        }
+
+        parse_scalar(
+            value,
+            &self.numeric_separators,
+            self.strict_int_parse,
+            self.float_policy,
+        )
+    }
+}
+
+impl Source for Environment {
+    fn clone_into_box(&self) -> Box<dyn Source + Send + Sync> {
+        Box::new((*self).clone())
+    }
+
+    fn collect(&self) -> Result<Map<String, Value>> {
+        let uri: String = "the environment".into();
+        let root = self.nest_under.as_ref().map(|root| format!("{}.", root));
+
+        self.collect_raw()?
+            .into_iter()
+            .map(|(key, value)| {
+                // List keys are given without the nesting
+                let list_key = match root {
+                    Some(ref root) => key.strip_prefix(root.as_str()).unwrap_or(&key),
+                    None => &key,
+                };
+                let value = self.to_value(&uri, list_key, value)?;
+
+                Ok((key, value))
+            })
+            .collect()
+    }
+}
+
+/// Settings of an [`Environment`] prepared once for all the variables it reads.
+struct Rules {
+    /// Separator replaced with `.` in keys, empty if none
+    separator: String,
+
+    /// Separator after the prefix
+    group_separator: String,
+
+    /// Lowercase prefix followed by its separator
+    prefix_pattern: Option<String>,
+
+    /// Lowercase suffix of the keys read from files
+    file_suffix: String,
+}
+
+/// The array written in JSON in the value of `key`.
+#[cfg(feature = "json")]
+fn parse_json_array(uri: &String, key: &str, value: &str) -> Result<Value> {
+    let invalid = |reason: String| {
+        ConfigError::Message(format!(
+            "value of key {:?} in the environment is not a JSON array: {}",
+            key, reason
+        ))
+    };
+
+    let parsed: serde_json::Value =
+        serde_json::from_str(value).map_err(|err| invalid(err.to_string()))?;
+    if !parsed.is_array() {
+        return Err(invalid(format!("found {}", value)));
+    }
+
+    Ok(crate::file::format::json::from_json_value(
+        Some(uri),
+        &parsed,
+    ))
+}
+
+/// `s` without `prefix`, if it starts with it ignoring case; `prefix` must be lowercase.
+fn strip_prefix_ignore_case<'a>(s: &'a str, prefix: &str) -> Option<&'a str> {
+    match s.get(..prefix.len()) {
+        Some(head) if head.to_lowercase() == prefix => Some(&s[prefix.len()..]),
+        _ => None,
+    }
+}
+
+/// Parses `value` as a boolean, an integer or a float, keeping it a string otherwise.
+///
+/// Numbers may contain `numeric_separators`, such as `_` in `1_000`. With `strict_int_parse`,
+/// integers with leading zeros stay strings; non-finite floats stay strings unless
+/// `float_policy` allows them. Shared by the sources parsing strings, like [`Environment`]
+/// and [`KeyValue`](crate::KeyValue).
+pub(crate) fn parse_scalar(
+    value: String,
+    numeric_separators: &[char],
+    strict_int_parse: bool,
+    float_policy: FloatPolicy,
+) -> ValueKind {
+    let stripped: Option<String> = if numeric_separators.is_empty() {
+        None
+    } else {
+        Some(
+            value
+                .chars()
+                .filter(|c| !numeric_separators.contains(c))
+                .collect(),
+        )
+    };
+    let number = stripped.as_deref().unwrap_or(&value);
+
+    // convert to lowercase because bool parsing expects all lowercase
+    if let Ok(parsed) = value.to_lowercase().parse::<bool>() {
+        ValueKind::Boolean(parsed)
+    } else if strict_int_parse && has_leading_zeros(number) {
+        // Numeric-looking identifiers such as `007`
+        ValueKind::String(value)
+    } else if let Ok(parsed) = number.parse::<i64>() {
+        ValueKind::Integer(parsed)
+    } else if let Some(parsed) = number
+        .parse::<f64>()
+        .ok()
+        .filter(|f| f.is_finite() || float_policy == FloatPolicy::Allow)
+    {
+        ValueKind::Float(parsed)
+    } else {
+        ValueKind::String(value)
+    }
+}
+
+/// Whether `s` is a number whose integer part starts with a superfluous zero, like `007`.
+fn has_leading_zeros(s: &str) -> bool {
+    let digits = s.trim_start_matches(|c| c == '+' || c == '-');
+    let mut chars = digits.chars();
+
+    chars.next() == Some('0') && chars.next().map_or(false, |c| c.is_ascii_digit())
+}
+
+/// `s` without `suffix`, if it ends with it ignoring case; `suffix` must be lowercase.
+fn strip_suffix_ignore_case<'a>(s: &'a str, suffix: &str) -> Option<&'a str> {
+    let start = s.len().checked_sub(suffix.len())?;
+
+    match s.get(start..) {
+        Some(tail) if tail.to_lowercase() == suffix => Some(&s[..start]),
+        _ => None,
+    }
+}
+
+impl Source for EnvironmentChain {
+    fn clone_into_box(&self) -> Box<dyn Source + Send + Sync> {
+        Box::new((*self).clone())
+    }
+
+    fn collect(&self) -> Result<Map<String, Value>> {
+        let mut m = Map::new();
 
-            m.insert(key, Value::new(Some(&uri), value));
+        // Later environments overwrite keys of earlier ones
+        for environment in &self.environments {
+            m.extend(environment.collect()?);
         }
 
         Ok(m)
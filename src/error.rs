@@ -10,6 +10,7 @@ pub enum Unexpected {
     Bool(bool),
     Integer(i64),
     Float(f64),
+    Number(String),
     Str(String),
     Unit,
     Seq,
@@ -22,6 +23,7 @@ impl fmt::Display for Unexpected {
             Unexpected::Bool(b) => write!(f, "boolean `{}`", b),
             Unexpected::Integer(i) => write!(f, "integer `{}`", i),
             Unexpected::Float(v) => write!(f, "floating point `{}`", v),
+            Unexpected::Number(ref n) => write!(f, "number `{}`", n),
             Unexpected::Str(ref s) => write!(f, "string {:?}", s),
             Unexpected::Unit => write!(f, "unit value"),
             Unexpected::Seq => write!(f, "sequence"),
@@ -30,6 +32,19 @@ impl fmt::Display for Unexpected {
     }
 }
 
+/// Location of a parse error in the text of a file, both numbers starting at 1.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {} column {}", self.line, self.column)
+    }
+}
+
 /// Represents all possible errors that can occur when working with
 /// configuration.
 pub enum ConfigError {
@@ -78,7 +93,61 @@ pub enum ConfigError {
     Foreign(Box<dyn Error + Send + Sync>),
 }
 
+/// The kind of a [`ConfigError`], without the data it carries.
+///
+/// Useful to match on errors, e.g. in tests, without comparing their messages.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum ConfigErrorKind {
+    /// See [`ConfigError::Frozen`].
+    Frozen,
+
+    /// See [`ConfigError::NotFound`].
+    NotFound,
+
+    /// See [`ConfigError::PathParse`].
+    PathParse,
+
+    /// See [`ConfigError::FileParse`].
+    FileParse,
+
+    /// See [`ConfigError::Type`].
+    Type,
+
+    /// See [`ConfigError::Message`].
+    Message,
+
+    /// See [`ConfigError::Foreign`].
+    Foreign,
+}
+
 impl ConfigError {
+    /// Returns the kind of this error.
+    pub fn kind(&self) -> ConfigErrorKind {
+        match *self {
+            ConfigError::Frozen => ConfigErrorKind::Frozen,
+            ConfigError::NotFound(_) => ConfigErrorKind::NotFound,
+            ConfigError::PathParse(_) => ConfigErrorKind::PathParse,
+            ConfigError::FileParse { .. } => ConfigErrorKind::FileParse,
+            ConfigError::Type { .. } => ConfigErrorKind::Type,
+            ConfigError::Message(_) => ConfigErrorKind::Message,
+            ConfigError::Foreign(_) => ConfigErrorKind::Foreign,
+        }
+    }
+
+    /// Where in the file the parser failed, for a [`ConfigError::FileParse`] raised by a format
+    /// that reports it.
+    ///
+    /// The message of the error usually mentions the position as well, this gives access to it
+    /// without picking apart the message of each format.
+    pub fn position(&self) -> Option<Position> {
+        match *self {
+            ConfigError::FileParse { ref cause, .. } => {
+                crate::file::format::error_position(cause.as_ref())
+            }
+            _ => None,
+        }
+    }
+
     // FIXME: pub(crate)
     #[doc(hidden)]
     pub fn invalid_type(
@@ -0,0 +1,99 @@
+use std::fmt;
+
+use crate::map::Map;
+use crate::value::{Value, ValueKind};
+
+/// Report of what a configuration would be, returned by
+/// [`ConfigBuilder::explain`](crate::ConfigBuilder::explain).
+///
+/// It is printable as a table listing, for each key, its final value and origin followed by
+/// the values it shadows.
+#[derive(Clone, Debug)]
+pub struct ConfigExplanation {
+    keys: Vec<ExplainedKey>,
+}
+
+/// A key of a [`ConfigExplanation`].
+#[derive(Clone, Debug)]
+pub struct ExplainedKey {
+    /// Path of the key, such as `database.url`
+    pub key: String,
+
+    /// Final value of the key, whose origin is the layer setting it
+    pub value: Value,
+
+    /// Values of the key in lower layers, shadowed by later ones, from the lowest
+    pub shadowed: Vec<Value>,
+}
+
+impl ConfigExplanation {
+    /// Lists the leaves of `cache` along with the values `shadowed` at their path.
+    pub(crate) fn new(cache: Value, mut shadowed: Map<String, Vec<Value>>) -> Self {
+        let mut leaves = Vec::new();
+        collect_leaves(&mut leaves, None, cache);
+        leaves.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        ConfigExplanation {
+            keys: leaves
+                .into_iter()
+                .map(|(key, value)| ExplainedKey {
+                    shadowed: shadowed.remove(&key).unwrap_or_default(),
+                    key,
+                    value,
+                })
+                .collect(),
+        }
+    }
+
+    /// All the keys with a value, sorted by path.
+    pub fn keys(&self) -> &[ExplainedKey] {
+        &self.keys
+    }
+
+    /// The explanation of `key`, if it has a value.
+    pub fn get(&self, key: &str) -> Option<&ExplainedKey> {
+        self.keys.iter().find(|explained| explained.key == key)
+    }
+}
+
+fn collect_leaves(leaves: &mut Vec<(String, Value)>, key: Option<String>, value: Value) {
+    match value.kind {
+        ValueKind::Table(table) => {
+            for (child, value) in table {
+                let child = match key {
+                    Some(ref key) => format!("{}.{}", key, child),
+                    None => child,
+                };
+                collect_leaves(leaves, Some(child), value);
+            }
+        }
+
+        _ => leaves.push((key.unwrap_or_default(), value)),
+    }
+}
+
+/// Writes `value` followed by its origin, if known.
+fn write_value(f: &mut fmt::Formatter, value: &Value) -> fmt::Result {
+    match value.origin() {
+        Some(origin) => write!(f, "{} ({})", value, origin),
+        None => write!(f, "{}", value),
+    }
+}
+
+impl fmt::Display for ConfigExplanation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for explained in &self.keys {
+            write!(f, "{} = ", explained.key)?;
+            write_value(f, &explained.value)?;
+            writeln!(f)?;
+
+            for value in explained.shadowed.iter().rev() {
+                write!(f, "    shadows ")?;
+                write_value(f, value)?;
+                writeln!(f)?;
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,235 @@
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io::{Cursor, Read};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use crate::error::*;
+use crate::file::format::ALL_EXTENSIONS;
+use crate::file::FileFormat;
+use crate::map::Map;
+use crate::path::Expression;
+use crate::source::Source;
+use crate::value::Value;
+
+/// Container format of an [`Archive`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ArchiveFormat {
+    /// ZIP archive, with stored or deflated entries
+    Zip,
+
+    /// Uncompressed tar archive
+    Tar,
+}
+
+/// A source reading configuration files bundled in a ZIP or tar archive.
+///
+/// Every file of the archive matching the [`entries`](Self::entries) pattern is parsed in the
+/// format given by its extension, and entries are merged in the lexicographic order of their
+/// paths, later ones overriding earlier ones. Entries whose extension is not a known format,
+/// such as a `README`, are skipped.
+#[derive(Clone, Debug)]
+pub struct Archive {
+    data: ArchiveData,
+
+    /// Name of the archive in origins and errors
+    name: String,
+
+    format: ArchiveFormat,
+
+    /// Glob matched against the paths of the entries
+    pattern: String,
+}
+
+#[derive(Clone)]
+enum ArchiveData {
+    Path(PathBuf),
+    Bytes(Arc<[u8]>),
+}
+
+impl fmt::Debug for ArchiveData {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ArchiveData::Path(ref path) => f.debug_tuple("Path").field(path).finish(),
+            ArchiveData::Bytes(ref bytes) => write!(f, "Bytes({} bytes)", bytes.len()),
+        }
+    }
+}
+
+impl Archive {
+    /// An archive read from `path` when collected.
+    pub fn new<P: AsRef<Path>>(path: P, format: ArchiveFormat) -> Self {
+        let path = path.as_ref();
+
+        Archive {
+            name: path.to_string_lossy().into_owned(),
+            data: ArchiveData::Path(path.to_path_buf()),
+            format,
+            pattern: "**".into(),
+        }
+    }
+
+    /// An archive already in memory, named `name` in origins and errors.
+    pub fn from_bytes<B: Into<Vec<u8>>>(name: &str, bytes: B, format: ArchiveFormat) -> Self {
+        Archive {
+            name: name.into(),
+            data: ArchiveData::Bytes(bytes.into().into()),
+            format,
+            pattern: "**".into(),
+        }
+    }
+
+    /// Only read the entries whose path matches `pattern`, all of them by default.
+    ///
+    /// In the pattern `?` matches any character and `*` any sequence of characters, except for
+    /// `/`, while `**` also matches across directories: `conf/*.toml` matches `conf/app.toml`
+    /// and `**/*.toml` any TOML entry.
+    pub fn entries(mut self, pattern: &str) -> Self {
+        self.pattern = pattern.into();
+        self
+    }
+
+    fn error(&self, entry: Option<&str>, cause: Box<dyn Error + Send + Sync>) -> ConfigError {
+        ConfigError::FileParse {
+            uri: Some(match entry {
+                Some(entry) => format!("{}:{}", self.name, entry),
+                None => self.name.clone(),
+            }),
+            cause,
+        }
+    }
+
+    fn read_entries(&self) -> Result<Entries> {
+        let bytes: Arc<[u8]> = match self.data {
+            ArchiveData::Path(ref path) => fs::read(path)
+                .map_err(|err| self.error(None, Box::new(err)))?
+                .into(),
+            ArchiveData::Bytes(ref bytes) => bytes.clone(),
+        };
+
+        let entries = match self.format {
+            ArchiveFormat::Zip => read_zip(&bytes),
+            ArchiveFormat::Tar => read_tar(&bytes),
+        };
+
+        entries.map_err(|err| self.error(None, err))
+    }
+}
+
+/// Paths and contents of the files of an archive.
+type Entries = Vec<(String, Vec<u8>)>;
+
+fn read_zip(bytes: &[u8]) -> std::result::Result<Entries, Box<dyn Error + Send + Sync>> {
+    let mut archive = zip::ZipArchive::new(Cursor::new(bytes))?;
+    let mut entries = Vec::with_capacity(archive.len());
+
+    for i in 0..archive.len() {
+        let mut file = archive.by_index(i)?;
+        if file.is_dir() {
+            continue;
+        }
+
+        let mut content = Vec::new();
+        file.read_to_end(&mut content)?;
+        entries.push((file.name().to_string(), content));
+    }
+
+    Ok(entries)
+}
+
+fn read_tar(bytes: &[u8]) -> std::result::Result<Entries, Box<dyn Error + Send + Sync>> {
+    let mut archive = tar::Archive::new(Cursor::new(bytes));
+    let mut entries = Vec::new();
+
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+
+        let name = entry.path()?.to_string_lossy().into_owned();
+        let mut content = Vec::new();
+        entry.read_to_end(&mut content)?;
+        entries.push((name, content));
+    }
+
+    Ok(entries)
+}
+
+impl Source for Archive {
+    fn clone_into_box(&self) -> Box<dyn Source + Send + Sync> {
+        Box::new((*self).clone())
+    }
+
+    fn collect(&self) -> Result<Map<String, Value>> {
+        let pattern: Vec<char> = self.pattern.chars().collect();
+
+        let mut entries: Entries = self
+            .read_entries()?
+            .into_iter()
+            .filter(|(name, _)| glob_matches(&pattern, &name.chars().collect::<Vec<_>>()))
+            .collect();
+        entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        let mut cache: Value = Map::<String, Value>::new().into();
+
+        for (name, content) in entries {
+            let format = match entry_format(&name) {
+                Some(format) => format,
+                None => continue,
+            };
+
+            let text =
+                String::from_utf8(content).map_err(|err| self.error(Some(&name), Box::new(err)))?;
+
+            let uri = format!("{}:{}", self.name, name);
+            let map = format
+                .parse(Some(&uri), &text)
+                .map_err(|cause| self.error(Some(&name), cause))?;
+
+            for (key, value) in map {
+                Expression::Identifier(key).set(&mut cache, value);
+            }
+        }
+
+        cache.into_table()
+    }
+}
+
+/// Format of an entry given by its extension.
+fn entry_format(name: &str) -> Option<FileFormat> {
+    let extension = Path::new(name).extension()?.to_string_lossy();
+
+    ALL_EXTENSIONS
+        .iter()
+        .find(|(_, extensions)| extensions.contains(&extension.as_ref()))
+        .map(|(format, _)| *format)
+}
+
+fn glob_matches(pattern: &[char], name: &[char]) -> bool {
+    match pattern {
+        [] => name.is_empty(),
+
+        // `**/` also matches no directory at all
+        ['*', '*', '/', rest @ ..] => {
+            glob_matches(rest, name)
+                || (0..=name.len()).any(|i| glob_matches(&pattern[2..], &name[i..]))
+        }
+        ['*', '*', rest @ ..] => (0..=name.len()).any(|i| glob_matches(rest, &name[i..])),
+
+        ['*', rest @ ..] => (0..=name.len())
+            .take_while(|&i| i == 0 || name[i - 1] != '/')
+            .any(|i| glob_matches(rest, &name[i..])),
+
+        ['?', rest @ ..] => match name {
+            [c, name @ ..] if *c != '/' => glob_matches(rest, name),
+            _ => false,
+        },
+
+        [c, rest @ ..] => match name {
+            [n, name @ ..] if n == c => glob_matches(rest, name),
+            _ => false,
+        },
+    }
+}
@@ -17,19 +17,11 @@ pub fn parse(
     }
 }
 
-fn from_json_value(uri: Option<&String>, value: &serde_json::Value) -> Value {
+pub(crate) fn from_json_value(uri: Option<&String>, value: &serde_json::Value) -> Value {
     match *value {
         serde_json::Value::String(ref value) => Value::new(uri, ValueKind::String(value.clone())),
 
-        serde_json::Value::Number(ref value) => {
-            if let Some(value) = value.as_i64() {
-                Value::new(uri, ValueKind::Integer(value))
-            } else if let Some(value) = value.as_f64() {
-                Value::new(uri, ValueKind::Float(value))
-            } else {
-                unreachable!();
-            }
-        }
+        serde_json::Value::Number(ref value) => Value::new(uri, from_json_number(value)),
 
         serde_json::Value::Bool(value) => Value::new(uri, ValueKind::Boolean(value)),
 
@@ -56,3 +48,30 @@ fn from_json_value(uri: Option<&String>, value: &serde_json::Value) -> Value {
         serde_json::Value::Null => Value::new(uri, ValueKind::Nil),
     }
 }
+
+#[cfg(not(feature = "arbitrary_precision"))]
+fn from_json_number(value: &serde_json::Number) -> ValueKind {
+    if let Some(value) = value.as_i64() {
+        ValueKind::Integer(value)
+    } else if let Some(value) = value.as_f64() {
+        ValueKind::Float(value)
+    } else {
+        unreachable!();
+    }
+}
+
+/// Keeps the literal of the numbers that would lose precision as an `Integer` or a `Float`.
+#[cfg(feature = "arbitrary_precision")]
+fn from_json_number(value: &serde_json::Number) -> ValueKind {
+    if let Some(value) = value.as_i64() {
+        return ValueKind::Integer(value);
+    }
+
+    let literal = value.to_string();
+    let is_integer = !literal.contains(&['.', 'e', 'E'][..]);
+
+    match literal.parse::<f64>() {
+        Ok(value) if !is_integer && value.is_finite() => ValueKind::Float(value),
+        _ => ValueKind::Number(literal),
+    }
+}
@@ -5,14 +5,15 @@
 use std::collections::HashMap;
 use std::error::Error;
 
+use crate::error::Position;
 use crate::map::Map;
 use crate::value::Value;
 
 #[cfg(feature = "toml")]
-mod toml;
+pub(crate) mod toml;
 
 #[cfg(feature = "json")]
-mod json;
+pub(crate) mod json;
 
 #[cfg(feature = "yaml")]
 mod yaml;
@@ -60,6 +61,47 @@ pub enum FileFormat {
     Json5,
 }
 
+/// Format-specific parsing behavior, set with [`File::with_format_options`](crate::File::with_format_options).
+///
+/// Options only affect the format they belong to and are ignored by the others.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
+pub struct FormatOptions {
+    #[cfg(feature = "toml")]
+    toml_datetime: TomlDatetime,
+}
+
+impl FormatOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// How TOML datetimes are represented, [`TomlDatetime::String`] by default.
+    #[cfg(feature = "toml")]
+    pub fn toml_datetime(mut self, datetime: TomlDatetime) -> Self {
+        self.toml_datetime = datetime;
+        self
+    }
+}
+
+/// Representation of TOML datetimes in the parsed configuration.
+#[cfg(feature = "toml")]
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum TomlDatetime {
+    /// Datetimes become strings in their RFC 3339 form, e.g. `1979-05-27T07:32:00Z`.
+    String,
+
+    /// Datetimes keep their type and can be deserialized into a
+    /// [`toml::value::Datetime`](::toml::value::Datetime).
+    Typed,
+}
+
+#[cfg(feature = "toml")]
+impl Default for TomlDatetime {
+    fn default() -> Self {
+        TomlDatetime::String
+    }
+}
+
 lazy_static! {
     #[doc(hidden)]
     // #[allow(unused_mut)] ?
@@ -108,10 +150,20 @@ impl FileFormat {
         self,
         uri: Option<&String>,
         text: &str,
+    ) -> Result<Map<String, Value>, Box<dyn Error + Send + Sync>> {
+        self.parse_with_options(uri, text, &FormatOptions::default())
+    }
+
+    #[allow(unused_variables)]
+    pub(crate) fn parse_with_options(
+        self,
+        uri: Option<&String>,
+        text: &str,
+        options: &FormatOptions,
     ) -> Result<Map<String, Value>, Box<dyn Error + Send + Sync>> {
         match self {
             #[cfg(feature = "toml")]
-            FileFormat::Toml => toml::parse(uri, text),
+            FileFormat::Toml => toml::parse(uri, text, options.toml_datetime),
 
             #[cfg(feature = "json")]
             FileFormat::Json => json::parse(uri, text),
@@ -133,3 +185,55 @@ impl FileFormat {
         }
     }
 }
+
+/// Position reported by the parser of one of the formats, if `cause` is its error.
+#[allow(unused_variables)]
+pub(crate) fn error_position(cause: &(dyn Error + 'static)) -> Option<Position> {
+    let position = |line, column| Some(Position { line, column });
+
+    #[cfg(feature = "toml")]
+    if let Some(err) = cause.downcast_ref::<::toml::de::Error>() {
+        // toml counts from 0
+        return err
+            .line_col()
+            .and_then(|(line, column)| position(line + 1, column + 1));
+    }
+
+    #[cfg(feature = "json")]
+    if let Some(err) = cause.downcast_ref::<serde_json::Error>() {
+        return position(err.line(), err.column());
+    }
+
+    #[cfg(feature = "yaml")]
+    if let Some(err) = cause.downcast_ref::<yaml_rust::ScanError>() {
+        // yaml_rust counts columns from 0
+        return position(err.marker().line(), err.marker().col() + 1);
+    }
+
+    #[cfg(feature = "hjson")]
+    if let Some(serde_hjson::Error::Syntax(_, line, column)) = cause.downcast_ref() {
+        return position(*line, *column);
+    }
+
+    #[cfg(feature = "ini")]
+    if let Some(err) = cause.downcast_ref::<::ini::ParseError>() {
+        // rust-ini counts lines from 0
+        return position(err.line + 1, err.col);
+    }
+
+    #[cfg(feature = "ron")]
+    if let Some(err) = cause.downcast_ref::<::ron::Error>() {
+        return position(err.position.line, err.position.col);
+    }
+
+    #[cfg(feature = "json5")]
+    if let Some(json5_rs::Error::Message {
+        location: Some(ref location),
+        ..
+    }) = cause.downcast_ref()
+    {
+        return position(location.line, location.column);
+    }
+
+    None
+}
@@ -1,15 +1,22 @@
 use std::error::Error;
 
+use crate::file::format::TomlDatetime;
 use crate::map::Map;
+#[cfg(feature = "toml_comments")]
+use crate::path::Expression;
 use crate::value::{Value, ValueKind};
 
+/// Field of the table through which `toml::value::Datetime` deserializes itself.
+const DATETIME_FIELD: &str = "$__toml_private_datetime";
+
 pub fn parse(
     uri: Option<&String>,
     text: &str,
+    datetime: TomlDatetime,
 ) -> Result<Map<String, Value>, Box<dyn Error + Send + Sync>> {
     // Parse a TOML value from the provided text
     // TODO: Have a proper error fire if the root of a file is ever not a Table
-    let value = from_toml_value(uri, &toml::from_str(text)?);
+    let value = from_toml_value(uri, &toml::from_str(text)?, datetime);
     match value.kind {
         ValueKind::Table(map) => Ok(map),
 
@@ -17,7 +24,11 @@ pub fn parse(
     }
 }
 
-fn from_toml_value(uri: Option<&String>, value: &toml::Value) -> Value {
+pub(crate) fn from_toml_value(
+    uri: Option<&String>,
+    value: &toml::Value,
+    datetime: TomlDatetime,
+) -> Value {
     match *value {
         toml::Value::String(ref value) => Value::new(uri, value.to_string()),
         toml::Value::Float(value) => Value::new(uri, value),
@@ -28,7 +39,7 @@ fn from_toml_value(uri: Option<&String>, value: &toml::Value) -> Value {
             let mut m = Map::new();
 
             for (key, value) in table {
-                m.insert(key.clone(), from_toml_value(uri, value));
+                m.insert(key.clone(), from_toml_value(uri, value, datetime));
             }
 
             Value::new(uri, m)
@@ -38,12 +49,101 @@ fn from_toml_value(uri: Option<&String>, value: &toml::Value) -> Value {
             let mut l = Vec::new();
 
             for value in array {
-                l.push(from_toml_value(uri, value));
+                l.push(from_toml_value(uri, value, datetime));
             }
 
             Value::new(uri, l)
         }
 
-        toml::Value::Datetime(ref datetime) => Value::new(uri, datetime.to_string()),
+        toml::Value::Datetime(ref value) => match datetime {
+            TomlDatetime::String => Value::new(uri, value.to_string()),
+            TomlDatetime::Typed => {
+                let mut m = Map::new();
+                m.insert(DATETIME_FIELD.into(), Value::new(uri, value.to_string()));
+
+                Value::new(uri, m)
+            }
+        },
+    }
+}
+
+/// Leading comments of the keys and tables of `text`, by path, without the `#` markers.
+///
+/// Only the comment lines right above a key or a table header are kept; a blank line ends
+/// the comment of the following key.
+#[cfg(feature = "toml_comments")]
+pub(crate) fn parse_comments(
+    text: &str,
+) -> Result<Map<String, String>, Box<dyn Error + Send + Sync>> {
+    let document: toml_edit::DocumentMut = text.parse()?;
+
+    let mut comments = Map::new();
+    collect_comments(document.as_table(), None, &mut comments);
+    Ok(comments)
+}
+
+#[cfg(feature = "toml_comments")]
+fn collect_comments(
+    table: &toml_edit::Table,
+    parent: Option<&Expression>,
+    comments: &mut Map<String, String>,
+) {
+    for (key, item) in table.iter() {
+        let expr = match parent {
+            Some(parent) => Expression::Child(Box::new(parent.clone()), key.to_string()),
+            None => Expression::Identifier(key.to_string()),
+        };
+
+        let key_decor = table.key(key).map(|key| key.leaf_decor());
+
+        match *item {
+            toml_edit::Item::Table(ref child) => {
+                // Headers like `[database]` carry the comment, dotted keys like `a.b = 1` do not
+                let decor = if child.is_dotted() || child.is_implicit() {
+                    key_decor
+                } else {
+                    Some(child.decor())
+                };
+                insert_comment(comments, &expr, decor);
+                collect_comments(child, Some(&expr), comments);
+            }
+
+            toml_edit::Item::ArrayOfTables(ref array) => {
+                for (index, child) in array.iter().enumerate() {
+                    let expr = Expression::Subscript(Box::new(expr.clone()), index as isize);
+                    insert_comment(comments, &expr, Some(child.decor()));
+                    collect_comments(child, Some(&expr), comments);
+                }
+            }
+
+            _ => insert_comment(comments, &expr, key_decor),
+        }
+    }
+}
+
+#[cfg(feature = "toml_comments")]
+fn insert_comment(
+    comments: &mut Map<String, String>,
+    expr: &Expression,
+    decor: Option<&toml_edit::Decor>,
+) {
+    let prefix = match decor
+        .and_then(|decor| decor.prefix())
+        .and_then(|p| p.as_str())
+    {
+        Some(prefix) => prefix,
+        None => return,
+    };
+
+    let mut lines = Vec::new();
+    for line in prefix.lines().map(str::trim) {
+        match line.strip_prefix('#') {
+            Some(comment) => lines.push(comment.strip_prefix(' ').unwrap_or(comment)),
+            None => lines.clear(),
+        }
+    }
+
+    if !lines.is_empty() {
+        comments.insert(expr.to_string(), lines.join("\n"));
     }
 }
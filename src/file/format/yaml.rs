@@ -1,29 +1,34 @@
 use std::error::Error;
-use std::fmt;
-use std::mem;
 
 use yaml_rust as yaml;
 
 use crate::map::Map;
+use crate::path::Expression;
 use crate::value::{Value, ValueKind};
 
+/// Parses every document of `text`, merging them in order.
+///
+/// Later documents override the keys of earlier ones, tables being merged deeply like the
+/// layers of a [`Config`](crate::Config), so a file can be split into a base document and
+/// documents refining it.
 pub fn parse(
     uri: Option<&String>,
     text: &str,
 ) -> Result<Map<String, Value>, Box<dyn Error + Send + Sync>> {
-    // Parse a YAML object from file
-    let mut docs = yaml::YamlLoader::load_from_str(text)?;
-    let root = match docs.len() {
-        0 => yaml::Yaml::Hash(yaml::yaml::Hash::new()),
-        1 => mem::replace(&mut docs[0], yaml::Yaml::Null),
-        n => {
-            return Err(Box::new(MultipleDocumentsError(n)));
+    // Parse the YAML documents from file
+    let docs = yaml::YamlLoader::load_from_str(text)?;
+    let mut root: Value = Map::<String, Value>::new().into();
+
+    for doc in &docs {
+        // TODO: Have a proper error fire if the root of a document is ever not a Table
+        if let ValueKind::Table(map) = from_yaml_value(uri, doc).kind {
+            for (key, value) in map {
+                Expression::Identifier(key).set(&mut root, value);
+            }
         }
-    };
+    }
 
-    // TODO: Have a proper error fire if the root of a file is ever not a Table
-    let value = from_yaml_value(uri, &root);
-    match value.kind {
+    match root.kind {
         ValueKind::Table(map) => Ok(map),
 
         _ => Ok(Map::new()),
@@ -67,18 +72,3 @@ fn from_yaml_value(uri: Option<&String>, value: &yaml::Yaml) -> Value {
         _ => Value::new(uri, ValueKind::Nil),
     }
 }
-
-#[derive(Debug, Copy, Clone)]
-struct MultipleDocumentsError(usize);
-
-impl fmt::Display for MultipleDocumentsError {
-    fn fmt(&self, format: &mut fmt::Formatter) -> fmt::Result {
-        write!(format, "Got {} YAML documents, expected 1", self.0)
-    }
-}
-
-impl Error for MultipleDocumentsError {
-    fn description(&self) -> &str {
-        "More than one YAML document provided"
-    }
-}
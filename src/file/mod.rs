@@ -1,17 +1,28 @@
-mod format;
+#[cfg(feature = "archive")]
+mod archive;
+pub(crate) mod format;
 pub mod source;
 
 use std::path::{Path, PathBuf};
+use std::str::FromStr;
 
+use crate::case::KeyCase;
 use crate::error::*;
 use crate::map::Map;
+use crate::path::Expression;
 use crate::source::Source;
 use crate::value::Value;
 
+#[cfg(feature = "archive")]
+pub use self::archive::{Archive, ArchiveFormat};
 pub use self::format::FileFormat;
+pub use self::format::FormatOptions;
+#[cfg(feature = "toml")]
+pub use self::format::TomlDatetime;
 use self::source::FileSource;
 
 pub use self::source::file::FileSourceFile;
+pub use self::source::stdin::FileSourceStdin;
 pub use self::source::string::FileSourceString;
 
 #[derive(Clone, Debug)]
@@ -24,63 +35,230 @@ where
     /// Format of file (which dictates what driver to use).
     format: Option<FileFormat>,
 
+    /// Formats tried in order when the content is parsed, if any
+    formats: Vec<FileFormat>,
+
     /// A required File will error if it cannot be found
     required: bool,
+
+    /// Path of the table used as the root of the configuration, instead of the whole file
+    root_key: Option<String>,
+
+    /// Format-specific parsing behavior
+    format_options: FormatOptions,
+
+    /// Replace dashes in keys with underscores
+    kebab_to_snake: bool,
+
+    /// Collect the comments of the keys
+    comments: bool,
 }
 
 impl File<source::string::FileSourceString> {
     pub fn from_str(s: &str, format: FileFormat) -> Self {
-        File {
-            format: Some(format),
-            required: true,
-            source: s.into(),
-        }
+        File::from_source(s.into(), Some(format))
+    }
+
+    /// Label the string with a name that is reported as its origin, e.g. in parse errors.
+    ///
+    /// Useful for configuration embedded with `include_str!`, which otherwise has no URI.
+    pub fn origin(mut self, origin: &str) -> Self {
+        self.source.set_origin(origin);
+        self
+    }
+}
+
+impl File<source::stdin::FileSourceStdin> {
+    /// Read the whole standard input and parse it as `format`.
+    ///
+    /// Standard input is read the first time the source is collected; later collections,
+    /// e.g. by [`Config::refresh_named`](crate::Config::refresh_named), reuse that content.
+    /// An empty input is an error unless the file is not [`required`](File::required).
+    pub fn from_stdin(format: FileFormat) -> Self {
+        File::from_source(source::stdin::FileSourceStdin::new(), Some(format))
+    }
+
+    /// Like [`from_stdin`](File::from_stdin), reading from `reader` instead.
+    ///
+    /// Any reader works, such as an embedded resource or an already opened stream. It is read
+    /// once, so refreshing the configuration parses the same content again. `origin` names the
+    /// reader, as the origin of its values and in errors.
+    pub fn from_reader<R: std::io::Read + Send + 'static>(
+        reader: R,
+        format: FileFormat,
+        origin: &str,
+    ) -> Self {
+        let mut source = source::stdin::FileSourceStdin::from_reader(reader);
+        source.set_origin(origin);
+
+        File::from_source(source, Some(format))
+    }
+
+    /// Name the input, as the origin of its values and in errors.
+    pub fn origin(mut self, origin: &str) -> Self {
+        self.source.set_origin(origin);
+        self
     }
 }
 
 impl File<source::file::FileSourceFile> {
+    /// A required file: collecting fails if it cannot be found.
     pub fn new(name: &str, format: FileFormat) -> Self {
+        File::from_source(source::file::FileSourceFile::new(name.into()), Some(format))
+    }
+
+    /// An optional file: if it cannot be found it is treated as empty.
+    ///
+    /// A file that exists but cannot be parsed is still an error.
+    pub fn optional(name: &str, format: FileFormat) -> Self {
+        File::new(name, format).required(false)
+    }
+
+    /// A required file parsed with the first of `formats` that accepts its content.
+    ///
+    /// Meant for files whose extension does not tell their format, such as one named just
+    /// `config`: the formats are tried in order and the first one parsing the file without
+    /// error is used. If none does, the error lists the failure of each format.
+    pub fn from_with_formats<P: AsRef<Path>>(path: P, formats: &[FileFormat]) -> Self {
         File {
-            format: Some(format),
-            required: true,
-            source: source::file::FileSourceFile::new(name.into()),
+            formats: formats.to_vec(),
+            ..File::from_source(
+                source::file::FileSourceFile::new(path.as_ref().to_path_buf()),
+                formats.first().copied(),
+            )
         }
     }
 
     /// Given the basename of a file, will attempt to locate a file by setting its
     /// extension to a registered format.
     pub fn with_name(name: &str) -> Self {
-        File {
-            format: None,
-            required: true,
-            source: source::file::FileSourceFile::new(name.into()),
+        File::from_source(source::file::FileSourceFile::new(name.into()), None)
+    }
+
+    /// A required file at `name` resolved against the directory `base` instead of the current
+    /// directory.
+    ///
+    /// A relative `base` is itself resolved against the current directory. Origins and errors
+    /// report the absolute path of the file.
+    pub fn relative_to<B: AsRef<Path>>(base: B, name: &str, format: FileFormat) -> Self {
+        File::from_source(
+            source::file::FileSourceFile::relative_to(base.as_ref(), name.as_ref()),
+            Some(format),
+        )
+    }
+
+    /// A required file at `name` in the directory of the running executable, for configuration
+    /// shipped next to the binary.
+    ///
+    /// Fails if the path of the executable cannot be determined.
+    pub fn beside_exe(name: &str, format: FileFormat) -> Result<Self> {
+        let exe = std::env::current_exe().map_err(|err| ConfigError::Foreign(Box::new(err)))?;
+        let dir = exe.parent().unwrap_or_else(|| Path::new("/"));
+
+        Ok(File::relative_to(dir, name, format))
+    }
+
+    /// Expand a leading `~` to the home directory and `${VAR}` to the value of the environment
+    /// variable `VAR` in the path, e.g. `${CONFIG_DIR}/app.toml`.
+    ///
+    /// The path is expanded each time the file is collected. An unset variable is reported
+    /// like a missing file: an error if the file is [`required`](File::required), an empty
+    /// configuration otherwise.
+    pub fn expand_path(mut self, expand: bool) -> Self {
+        self.source.set_expand(expand);
+        self
+    }
+
+    fn exists(&self) -> bool {
+        self.source.exists(self.format)
+    }
+}
+
+/// The files sharing a basename in several formats, merged in order, see
+/// [`ConfigBuilder::add_basename`](crate::ConfigBuilder::add_basename).
+#[derive(Clone, Debug)]
+pub(crate) struct FileSet {
+    basename: String,
+    formats: Vec<FileFormat>,
+
+    /// Fail if none of the files exists
+    required: bool,
+}
+
+impl FileSet {
+    pub(crate) fn new(basename: &str, formats: &[FileFormat], required: bool) -> Self {
+        FileSet {
+            basename: basename.into(),
+            formats: formats.to_vec(),
+            required,
         }
     }
 }
 
+impl Source for FileSet {
+    fn clone_into_box(&self) -> Box<dyn Source + Send + Sync> {
+        Box::new((*self).clone())
+    }
+
+    fn collect(&self) -> Result<Map<String, Value>> {
+        let mut cache: Value = Map::<String, Value>::new().into();
+        let mut found = false;
+
+        for &format in &self.formats {
+            let file = File::new(&self.basename, format);
+            if !file.exists() {
+                continue;
+            }
+
+            found = true;
+            for (key, value) in file.collect()? {
+                Expression::Identifier(key).set(&mut cache, value);
+            }
+        }
+
+        if !found && self.required {
+            return Err(ConfigError::Foreign(Box::new(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!(
+                    "configuration file \"{}\" not found in any of the formats {:?}",
+                    self.basename, self.formats
+                ),
+            ))));
+        }
+
+        cache.into_table()
+    }
+}
+
 impl<'a> From<&'a Path> for File<source::file::FileSourceFile> {
     fn from(path: &'a Path) -> Self {
-        File {
-            format: None,
-            required: true,
-            source: source::file::FileSourceFile::new(path.to_path_buf()),
-        }
+        File::from_source(source::file::FileSourceFile::new(path.to_path_buf()), None)
     }
 }
 
 impl From<PathBuf> for File<source::file::FileSourceFile> {
     fn from(path: PathBuf) -> Self {
+        File::from_source(source::file::FileSourceFile::new(path), None)
+    }
+}
+
+impl<T: FileSource> File<T> {
+    fn from_source(source: T, format: Option<FileFormat>) -> Self {
         File {
-            format: None,
+            source,
+            format,
+            formats: Vec::new(),
             required: true,
-            source: source::file::FileSourceFile::new(path),
+            root_key: None,
+            format_options: FormatOptions::default(),
+            kebab_to_snake: false,
+            comments: false,
         }
     }
-}
 
-impl<T: FileSource> File<T> {
     pub fn format(mut self, format: FileFormat) -> Self {
         self.format = Some(format);
+        self.formats.clear();
         self
     }
 
@@ -88,6 +266,43 @@ impl<T: FileSource> File<T> {
         self.required = required;
         self
     }
+
+    /// Only use the table at `key`, such as `myapp` or `apps.myapp`, as the root of the
+    /// configuration, ignoring the rest of the file.
+    ///
+    /// A missing key is treated like a missing file: an error if the file is
+    /// [`required`](File::required), an empty configuration otherwise.
+    pub fn root_key(mut self, key: &str) -> Self {
+        self.root_key = Some(key.into());
+        self
+    }
+
+    /// Convert `kebab-case` keys, common in TOML files such as Cargo manifests, to `snake_case`
+    /// when parsing, so that `max-retries` becomes `max_retries` and matches Rust field names.
+    ///
+    /// Keys at every depth are converted, after the [`root_key`](File::root_key) is extracted.
+    /// If a table has both `max-retries` and `max_retries`, the value written in snake case wins.
+    pub fn kebab_to_snake(mut self, enable: bool) -> Self {
+        self.kebab_to_snake = enable;
+        self
+    }
+
+    /// Collect the comments right above each key, exposed by
+    /// [`Config::comment`](crate::Config::comment) once the configuration is built.
+    ///
+    /// Only TOML files have their comments collected. The file is read a second time for
+    /// them, with a parser that keeps comments.
+    #[cfg(feature = "toml_comments")]
+    pub fn with_comments(mut self, comments: bool) -> Self {
+        self.comments = comments;
+        self
+    }
+
+    /// Set format-specific parsing options.
+    pub fn with_format_options(mut self, options: FormatOptions) -> Self {
+        self.format_options = options;
+        self
+    }
 }
 
 impl<T: FileSource> Source for File<T>
@@ -117,9 +332,108 @@ where
             }
         };
 
-        // Parse the string using the given format
-        format
-            .parse(uri.as_ref(), &contents)
-            .map_err(|cause| ConfigError::FileParse { uri, cause })
+        let map = if self.formats.len() > 1 {
+            self.parse_first(uri.clone(), &contents)?
+        } else {
+            // Parse the string using the given format
+            format
+                .parse_with_options(uri.as_ref(), &contents, &self.format_options)
+                .map_err(|cause| ConfigError::FileParse {
+                    uri: uri.clone(),
+                    cause,
+                })?
+        };
+
+        let map = match self.root_key {
+            Some(ref key) => self.extract_root(key, uri, map)?,
+            None => map,
+        };
+
+        Ok(if self.kebab_to_snake {
+            KeyCase::KebabToSnake.convert_table(map)
+        } else {
+            map
+        })
+    }
+
+    #[allow(unused_variables)]
+    fn comments(&self) -> Result<Map<String, String>> {
+        if !self.comments {
+            return Ok(Map::new());
+        }
+
+        let (uri, contents, format) = match self.source.resolve(self.format) {
+            Ok(resolved) => resolved,
+            Err(_) if !self.required => return Ok(Map::new()),
+            Err(error) => return Err(ConfigError::Foreign(error)),
+        };
+
+        let comments: Map<String, String> = match format {
+            #[cfg(feature = "toml_comments")]
+            FileFormat::Toml => format::toml::parse_comments(&contents)
+                .map_err(|cause| ConfigError::FileParse { uri, cause })?,
+
+            _ => Map::new(),
+        };
+
+        let root = self.root_key.as_ref().map(|key| format!("{}.", key));
+
+        Ok(comments
+            .into_iter()
+            .filter_map(|(key, comment)| match root {
+                Some(ref root) => key
+                    .strip_prefix(root.as_str())
+                    .map(|key| (key.to_string(), comment)),
+                None => Some((key, comment)),
+            })
+            .map(|(key, comment)| {
+                if self.kebab_to_snake {
+                    (KeyCase::KebabToSnake.convert(&key), comment)
+                } else {
+                    (key, comment)
+                }
+            })
+            .collect())
+    }
+}
+
+impl<T: FileSource> File<T> {
+    /// The table at `key` of the parsed `map`.
+    fn extract_root(
+        &self,
+        key: &str,
+        uri: Option<String>,
+        map: Map<String, Value>,
+    ) -> Result<Map<String, Value>> {
+        let expr = Expression::from_str(key)?;
+        let mut file: Value = map.into();
+
+        match expr.get_mut(&mut file) {
+            Some(root) => std::mem::take(root)
+                .into_table()
+                .map_err(|err| err.extend_with_key(key)),
+            None if !self.required => Ok(Map::new()),
+            None => Err(ConfigError::FileParse {
+                uri,
+                cause: format!("root key {:?} not found", key).into(),
+            }),
+        }
+    }
+
+    /// Parses `contents` with the first of the candidate formats that accepts it.
+    fn parse_first(&self, uri: Option<String>, contents: &str) -> Result<Map<String, Value>> {
+        let mut failures = Vec::with_capacity(self.formats.len());
+
+        for format in &self.formats {
+            match format.parse_with_options(uri.as_ref(), contents, &self.format_options) {
+                Ok(map) => return Ok(map),
+                Err(cause) => failures.push(format!("{:?}: {}", format, cause)),
+            }
+        }
+
+        Err(ConfigError::FileParse {
+            uri,
+            cause: format!("no format could parse the file ({})", failures.join("; ")).into(),
+        })
     }
 }
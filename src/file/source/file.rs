@@ -13,19 +13,58 @@ use crate::file::{FileFormat, FileSource};
 pub struct FileSourceFile {
     /// Path of configuration file
     name: PathBuf,
+
+    /// Expand `~` and `${VAR}` in the path when resolving it
+    expand: bool,
+
+    /// Report the absolute path of the file in origins and errors
+    absolute: bool,
 }
 
 impl FileSourceFile {
     pub fn new(name: PathBuf) -> FileSourceFile {
-        FileSourceFile { name }
+        FileSourceFile {
+            name,
+            expand: false,
+            absolute: false,
+        }
+    }
+
+    /// A file at `name` resolved against the directory `base`, reported by its absolute path.
+    pub(crate) fn relative_to(base: &Path, name: &Path) -> FileSourceFile {
+        let base = match env::current_dir() {
+            Ok(dir) => dir.join(base),
+            Err(_) => base.to_path_buf(),
+        };
+
+        FileSourceFile {
+            name: base.join(name),
+            expand: false,
+            absolute: true,
+        }
+    }
+
+    pub(crate) fn set_expand(&mut self, expand: bool) {
+        self.expand = expand;
+    }
+
+    /// Whether the file can be found, with one of the extensions of `format_hint` if set.
+    pub(crate) fn exists(&self, format_hint: Option<FileFormat>) -> bool {
+        self.find_file(format_hint).is_ok()
     }
 
     fn find_file(
         &self,
         format_hint: Option<FileFormat>,
     ) -> Result<(PathBuf, FileFormat), Box<dyn Error + Send + Sync>> {
+        let name = if self.expand {
+            expand_path(&self.name)?
+        } else {
+            self.name.clone()
+        };
+
         // First check for an _exact_ match
-        let mut filename = env::current_dir()?.as_path().join(self.name.clone());
+        let mut filename = env::current_dir()?.as_path().join(&name);
         if filename.is_file() {
             return match format_hint {
                 Some(format) => Ok((filename, format)),
@@ -81,7 +120,7 @@ impl FileSourceFile {
             io::ErrorKind::NotFound,
             format!(
                 "configuration file \"{}\" not found",
-                self.name.to_string_lossy()
+                name.to_string_lossy()
             ),
         )))
     }
@@ -98,8 +137,8 @@ impl FileSource for FileSourceFile {
         // Attempt to use a relative path for the URI
         let base = env::current_dir()?;
         let uri = match path_relative_from(&filename, &base) {
-            Some(value) => value,
-            None => filename.clone(),
+            Some(value) if !self.absolute => value,
+            _ => filename.clone(),
         };
 
         // Read contents from file
@@ -111,6 +150,47 @@ impl FileSource for FileSourceFile {
     }
 }
 
+/// `path` with a leading `~` replaced by the home directory and each `${VAR}` by the value of
+/// the environment variable `VAR`.
+fn expand_path(path: &Path) -> Result<PathBuf, Box<dyn Error + Send + Sync>> {
+    // Paths that are not valid UTF-8 are kept as is
+    let path = match path.to_str() {
+        Some(path) => path,
+        None => return Ok(path.to_path_buf()),
+    };
+
+    let var = |name: &str| {
+        env::var(name).map_err(|_| {
+            format!(
+                "environment variable {:?} in path {:?} is not set",
+                name, path
+            )
+        })
+    };
+
+    let mut expanded = String::with_capacity(path.len());
+    let mut rest = path;
+
+    if rest == "~" || rest.starts_with("~/") || rest.starts_with("~\\") {
+        let home = if cfg!(windows) { "USERPROFILE" } else { "HOME" };
+        expanded.push_str(&var(home)?);
+        rest = &rest[1..];
+    }
+
+    while let Some(start) = rest.find("${") {
+        let end = rest[start..]
+            .find('}')
+            .ok_or_else(|| format!("unclosed `${{` in path {:?}", path))?;
+
+        expanded.push_str(&rest[..start]);
+        expanded.push_str(&var(&rest[start + 2..start + end])?);
+        rest = &rest[start + end + 1..];
+    }
+    expanded.push_str(rest);
+
+    Ok(expanded.into())
+}
+
 // TODO: This should probably be a crate
 // https://github.com/rust-lang/rust/blob/master/src/librustc_trans/back/rpath.rs#L128
 fn path_relative_from(path: &Path, base: &Path) -> Option<PathBuf> {
@@ -1,4 +1,5 @@
 pub mod file;
+pub mod stdin;
 pub mod string;
 
 use std::error::Error;
@@ -0,0 +1,84 @@
+use std::error::Error;
+use std::fmt;
+use std::io::{self, Read};
+use std::sync::{Arc, Mutex};
+
+use crate::file::{FileFormat, FileSource};
+
+/// Describes a file sourced from the standard input, or any other reader
+///
+/// The reader is consumed the first time the source is collected and its content is
+/// kept for later collections, such as a refresh. Clones share the same reader and content.
+#[derive(Clone)]
+pub struct FileSourceStdin {
+    state: Arc<Mutex<ReaderState>>,
+
+    /// Name of the reader in origins and errors
+    origin: String,
+}
+
+struct ReaderState {
+    /// Reader not consumed yet
+    reader: Option<Box<dyn Read + Send>>,
+
+    /// Content read from the reader
+    content: Option<String>,
+}
+
+impl FileSourceStdin {
+    pub(crate) fn new() -> Self {
+        Self::from_reader(io::stdin())
+    }
+
+    pub(crate) fn from_reader<R: Read + Send + 'static>(reader: R) -> Self {
+        FileSourceStdin {
+            state: Arc::new(Mutex::new(ReaderState {
+                reader: Some(Box::new(reader)),
+                content: None,
+            })),
+            origin: "stdin".into(),
+        }
+    }
+
+    pub(crate) fn set_origin(&mut self, origin: &str) {
+        self.origin = origin.into();
+    }
+}
+
+impl fmt::Debug for FileSourceStdin {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("FileSourceStdin")
+            .field("origin", &self.origin)
+            .finish()
+    }
+}
+
+impl FileSource for FileSourceStdin {
+    fn resolve(
+        &self,
+        format_hint: Option<FileFormat>,
+    ) -> Result<(Option<String>, String, FileFormat), Box<dyn Error + Send + Sync>> {
+        let mut state = self
+            .state
+            .lock()
+            .map_err(|_| format!("{} source was poisoned by a panic", self.origin))?;
+
+        if let Some(mut reader) = state.reader.take() {
+            let mut content = String::new();
+            reader.read_to_string(&mut content)?;
+            state.content = Some(content);
+        }
+
+        let content = match state.content {
+            Some(ref content) if !content.trim().is_empty() => content.clone(),
+            Some(_) => return Err(format!("{} is empty", self.origin).into()),
+            None => return Err(format!("{} could not be read", self.origin).into()),
+        };
+
+        Ok((
+            Some(self.origin.clone()),
+            content,
+            format_hint.expect("from_stdin and from_reader require a set file format"),
+        ))
+    }
+}
@@ -4,11 +4,26 @@ use crate::file::{FileFormat, FileSource};
 
 /// Describes a file sourced from a string
 #[derive(Clone, Debug)]
-pub struct FileSourceString(String);
+pub struct FileSourceString {
+    /// Contents of the configuration
+    content: String,
+
+    /// Optional name reported as the origin of values and errors
+    origin: Option<String>,
+}
+
+impl FileSourceString {
+    pub(crate) fn set_origin(&mut self, origin: &str) {
+        self.origin = Some(origin.into());
+    }
+}
 
 impl<'a> From<&'a str> for FileSourceString {
     fn from(s: &'a str) -> Self {
-        FileSourceString(s.into())
+        FileSourceString {
+            content: s.into(),
+            origin: None,
+        }
     }
 }
 
@@ -18,8 +33,8 @@ impl FileSource for FileSourceString {
         format_hint: Option<FileFormat>,
     ) -> Result<(Option<String>, String, FileFormat), Box<dyn Error + Send + Sync>> {
         Ok((
-            None,
-            self.0.clone(),
+            self.origin.clone(),
+            self.content.clone(),
             format_hint.expect("from_str requires a set file format"),
         ))
     }
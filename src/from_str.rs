@@ -0,0 +1,67 @@
+use std::fmt;
+use std::marker::PhantomData;
+use std::ops::{Deref, DerefMut};
+use std::str;
+
+use serde::de::{self, Deserialize, Deserializer, Visitor};
+
+/// Deserializes a `T` from its string form, with its [`FromStr`](std::str::FromStr)
+/// implementation.
+///
+/// This bridges types that can be parsed but do not implement `Deserialize`, or that
+/// deserialize differently, e.g. `config.get::<FromStr<Ipv4Addr>>("server.ip")`. Scalars
+/// such as integers are parsed from their display form, and parsing errors are reported
+/// with the value that failed to parse.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FromStr<T>(pub T);
+
+impl<T> FromStr<T> {
+    /// Unwrap the parsed value.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> Deref for FromStr<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for FromStr<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl<'de, T> Deserialize<'de> for FromStr<T>
+where
+    T: str::FromStr,
+    T::Err: fmt::Display,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct FromStrVisitor<T>(PhantomData<T>);
+
+        impl<'de, T> Visitor<'de> for FromStrVisitor<T>
+        where
+            T: str::FromStr,
+            T::Err: fmt::Display,
+        {
+            type Value = FromStr<T>;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a string")
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<FromStr<T>, E> {
+                v.parse()
+                    .map(FromStr)
+                    .map_err(|err| E::custom(format!("invalid value {:?}: {}", v, err)))
+            }
+        }
+
+        deserializer.deserialize_string(FromStrVisitor(PhantomData))
+    }
+}
@@ -53,26 +53,55 @@ extern crate ron;
 #[cfg(feature = "json5")]
 extern crate json5_rs;
 
+#[macro_use]
+mod macros;
+
 pub mod builder;
+mod case;
 mod config;
 mod de;
 mod env;
 mod error;
+mod explain;
 mod file;
+mod from_str;
 mod map;
 mod path;
+mod placeholder;
+mod schema;
 mod ser;
-mod source;
+#[cfg(any(feature = "json", feature = "toml"))]
+mod serde_value;
+pub mod source;
+mod units;
 mod value;
 
 pub use crate::builder::AsyncConfigBuilder;
 pub use crate::builder::ConfigBuilder;
-pub use crate::config::Config;
-pub use crate::env::Environment;
-pub use crate::error::ConfigError;
-pub use crate::file::{File, FileFormat, FileSourceFile, FileSourceString};
+pub use crate::builder::Tier;
+pub use crate::case::KeyCase;
+pub use crate::config::{Config, SharedConfig};
+pub use crate::de::double_option;
+pub use crate::env::{Environment, EnvironmentChain};
+pub use crate::error::{ConfigError, ConfigErrorKind, Position};
+pub use crate::explain::{ConfigExplanation, ExplainedKey};
+#[cfg(feature = "toml")]
+pub use crate::file::TomlDatetime;
+#[cfg(feature = "archive")]
+pub use crate::file::{Archive, ArchiveFormat};
+pub use crate::file::{
+    File, FileFormat, FileSourceFile, FileSourceStdin, FileSourceString, FormatOptions,
+};
+pub use crate::from_str::FromStr;
 pub use crate::map::Map;
+#[cfg(any(feature = "json", feature = "toml"))]
+pub use crate::serde_value::SerdeValue;
 pub use crate::source::AsyncSource;
+pub use crate::source::Fallback;
+pub use crate::source::Prefixed;
 pub use crate::source::Source;
+pub use crate::source::{KeyValue, KvBackend};
+pub use crate::units::{ByteSize, HumanDuration};
+pub use crate::value::FloatPolicy;
 pub use crate::value::Value;
 pub use crate::value::ValueKind;
@@ -0,0 +1,50 @@
+/// Builds a configuration key from a path written without quotes, checking its shape at
+/// compile time.
+///
+/// The path is a sequence of identifiers separated by dots, each optionally followed by
+/// subscripts, and expands to a `&'static str` usable with [`Config::get`](crate::Config::get):
+///
+/// ```rust
+/// # use config::config_key;
+/// assert_eq!(config_key!(app.database.url), "app.database.url");
+/// assert_eq!(config_key!(servers[0].host), "servers[0].host");
+/// assert_eq!(config_key!(servers[-1]), "servers[-1]");
+/// ```
+///
+/// Whether the key exists is only known at runtime, but malformed paths do not compile:
+///
+/// ```rust,compile_fail
+/// # use config::config_key;
+/// let key = config_key!(app..url);
+/// ```
+///
+/// ```rust,compile_fail
+/// # use config::config_key;
+/// let key = config_key!(app.database.);
+/// ```
+///
+/// ```rust,compile_fail
+/// # use config::config_key;
+/// let key = config_key!(servers[first]);
+/// ```
+///
+/// ```rust,compile_fail
+/// # use config::config_key;
+/// let key = config_key!(servers["first"]);
+/// ```
+///
+/// Keys that are not identifiers, such as `max-retries`, need to be written as strings.
+#[macro_export]
+macro_rules! config_key {
+    ($first:ident $([$first_index:literal])* $(. $segment:ident $([$index:literal])*)*) => {{
+        // Only integers are valid subscripts
+        $(const _: isize = $first_index;)*
+        $($(const _: isize = $index;)*)*
+
+        concat!(
+            stringify!($first),
+            $("[", $first_index, "]",)*
+            $(".", stringify!($segment), $("[", $index, "]",)*)*
+        )
+    }};
+}
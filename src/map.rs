@@ -1,4 +1,13 @@
+/// Map used for the tables of a configuration.
+///
+/// Without the `preserve_order` feature this is a [`HashMap`](std::collections::HashMap), whose
+/// iteration order is unspecified, and tables are deserialized in the sorted order of their keys
+/// so that the output is the same across runs. With `preserve_order` it is an
+/// `indexmap::IndexMap`, which keeps keys in the order they were first inserted,
+/// and tables are deserialized in that order.
 #[cfg(not(feature = "preserve_order"))]
 pub type Map<K, V> = std::collections::HashMap<K, V>;
+/// Map used for the tables of a configuration, keeping keys in the order they were first
+/// inserted; tables are deserialized in that order.
 #[cfg(feature = "preserve_order")]
 pub type Map<K, V> = indexmap::IndexMap<K, V>;
@@ -1,3 +1,4 @@
+use std::fmt;
 use std::str::FromStr;
 
 use crate::error::*;
@@ -21,6 +22,16 @@ impl FromStr for Expression {
     }
 }
 
+impl fmt::Display for Expression {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Expression::Identifier(ref id) => write!(f, "{}", id),
+            Expression::Child(ref expr, ref key) => write!(f, "{}.{}", expr, key),
+            Expression::Subscript(ref expr, index) => write!(f, "{}[{}]", expr, index),
+        }
+    }
+}
+
 fn sindex_to_uindex(index: isize, len: usize) -> usize {
     if index >= 0 {
         index as usize
@@ -30,6 +41,11 @@ fn sindex_to_uindex(index: isize, len: usize) -> usize {
 }
 
 impl Expression {
+    /// Parses a path whose segments are separated by `separator` rather than `.`.
+    pub(crate) fn parse_with_separator(key: &str, separator: char) -> Result<Expression> {
+        parser::from_str_with_separator(key, separator).map_err(ConfigError::PathParse)
+    }
+
     pub fn get(self, root: &Value) -> Option<&Value> {
         match self {
             Expression::Identifier(id) => {
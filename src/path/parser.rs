@@ -2,7 +2,7 @@ use std::str::FromStr;
 
 use nom::{
     branch::alt,
-    bytes::complete::{is_a, tag},
+    bytes::complete::{is_a, tag, take_till1},
     character::complete::{char, digit1, space0},
     combinator::{map, map_res, opt, recognize},
     error::ErrorKind,
@@ -31,15 +31,31 @@ fn integer(i: &str) -> IResult<&str, isize> {
     )(i)
 }
 
-fn ident(i: &str) -> IResult<&str, Expression> {
-    map(raw_ident, Expression::Identifier)(i)
+/// A key segment: an identifier with the default `.` separator, otherwise anything up to the
+/// next separator or subscript.
+fn segment(separator: char, i: &str) -> IResult<&str, String> {
+    if separator == '.' {
+        raw_ident(i)
+    } else {
+        map(take_till1(|c| c == separator || c == '['), |s: &str| {
+            s.to_string()
+        })(i)
+    }
+}
+
+fn ident(separator: char, i: &str) -> IResult<&str, Expression> {
+    map(|i| segment(separator, i), Expression::Identifier)(i)
 }
 
-fn postfix<'a>(expr: Expression) -> impl FnMut(&'a str) -> IResult<&'a str, Expression> {
+fn postfix<'a>(
+    expr: Expression,
+    separator: char,
+) -> impl FnMut(&'a str) -> IResult<&'a str, Expression> {
     let e2 = expr.clone();
-    let child = map(preceded(tag("."), raw_ident), move |id| {
-        Expression::Child(Box::new(expr.clone()), id)
-    });
+    let child = map(
+        preceded(char(separator), move |i| segment(separator, i)),
+        move |id| Expression::Child(Box::new(expr.clone()), id),
+    );
 
     let subscript = map(delimited(char('['), integer, char(']')), move |num| {
         Expression::Subscript(Box::new(e2.clone()), num)
@@ -49,10 +65,18 @@ fn postfix<'a>(expr: Expression) -> impl FnMut(&'a str) -> IResult<&'a str, Expr
 }
 
 pub fn from_str(input: &str) -> Result<Expression, ErrorKind> {
-    match ident(input) {
+    from_str_with_separator(input, '.')
+}
+
+/// Parses a path whose segments are separated by `separator` rather than `.`.
+///
+/// With a separator other than `.`, segments are taken literally, so `sites/a.com` with `/`
+/// is the key `a.com` of the table `sites`.
+pub fn from_str_with_separator(input: &str, separator: char) -> Result<Expression, ErrorKind> {
+    match ident(separator, input) {
         Ok((mut rem, mut expr)) => {
             while !rem.is_empty() {
-                match postfix(expr)(rem) {
+                match postfix(expr, separator)(rem) {
                     Ok((rem_, expr_)) => {
                         rem = rem_;
                         expr = expr_;
@@ -128,4 +152,15 @@ mod test {
 
         assert_eq!(parsed, expected);
     }
+
+    #[test]
+    fn test_separator() {
+        let parsed: Expression = from_str_with_separator("sites/a.com[0]", '/').unwrap();
+        let expected = Subscript(
+            Box::new(Child(Box::new(Identifier("sites".into())), "a.com".into())),
+            0,
+        );
+
+        assert_eq!(parsed, expected);
+    }
 }
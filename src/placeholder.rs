@@ -0,0 +1,75 @@
+//! Detection of `${...}` references in string values.
+
+use std::str::FromStr;
+
+use crate::path::Expression;
+use crate::value::{Value, ValueKind};
+
+/// Names referenced as `${name}` in `text`, in order of appearance.
+///
+/// An opening `${` without a closing `}` is not a reference.
+pub(crate) fn references(text: &str) -> Vec<&str> {
+    let mut names = Vec::new();
+    let mut rest = text;
+
+    while let Some(start) = rest.find("${") {
+        let after = &rest[start + 2..];
+
+        match after.find('}') {
+            Some(end) => {
+                names.push(&after[..end]);
+                rest = &after[end + 1..];
+            }
+
+            None => break,
+        }
+    }
+
+    names
+}
+
+/// References in the string values of `root` that are neither a path of `root` nor the name
+/// of an environment variable, sorted and without duplicates.
+pub(crate) fn unresolved(root: &Value) -> Vec<String> {
+    let mut names = Vec::new();
+    collect_unresolved(root, root, &mut names);
+
+    names.sort();
+    names.dedup();
+    names
+}
+
+fn collect_unresolved(root: &Value, value: &Value, names: &mut Vec<String>) {
+    match value.kind {
+        ValueKind::String(ref text) => {
+            for name in references(text) {
+                if !resolves(root, name) {
+                    names.push(name.to_string());
+                }
+            }
+        }
+
+        ValueKind::Table(ref table) => {
+            for value in table.values() {
+                collect_unresolved(root, value, names);
+            }
+        }
+
+        ValueKind::Array(ref array) => {
+            for value in array {
+                collect_unresolved(root, value, names);
+            }
+        }
+
+        _ => {}
+    }
+}
+
+fn resolves(root: &Value, name: &str) -> bool {
+    let in_config = match Expression::from_str(name) {
+        Ok(expr) => expr.get(root).is_some(),
+        Err(_) => false,
+    };
+
+    in_config || (!name.is_empty() && std::env::var_os(name).is_some())
+}
@@ -0,0 +1,397 @@
+//! Inspection of the fields a type expects when deserialized, without any input data.
+//!
+//! The type is deserialized from placeholder values: its `Deserialize` implementation
+//! reports the fields of the struct, and leaving out one of them tells whether serde
+//! can do without it (`Option`, `#[serde(default)]`) or not.
+
+use std::fmt;
+
+use serde::de::{self, DeserializeOwned, IntoDeserializer, Visitor};
+
+use crate::error::*;
+
+/// Name and fields of the struct `T` deserializes from.
+pub(crate) fn struct_fields<T: DeserializeOwned>() -> Result<(&'static str, &'static [&'static str])>
+{
+    match T::deserialize(Recorder) {
+        Err(ProbeError::Struct(name, fields)) => Ok((name, fields)),
+        _ => Err(ConfigError::Message(
+            "only structs can be bound to the environment".into(),
+        )),
+    }
+}
+
+/// Names accepted for each field of the struct `T`, its own name first, followed by the
+/// names given with `#[serde(alias)]`.
+///
+/// Serde lists the aliases among the fields of the struct; a name is an alias of a field if
+/// giving both fails as a duplicate field.
+fn field_names<T: DeserializeOwned>() -> Result<Vec<Vec<&'static str>>> {
+    let (_, fields) = struct_fields::<T>()?;
+    let mut groups: Vec<Vec<&'static str>> = Vec::new();
+
+    for &name in fields {
+        let mut names: Vec<&'static str> = groups.iter().map(|group| group[0]).collect();
+        names.push(name);
+
+        let field = match T::deserialize(Probe { names }) {
+            Err(ProbeError::Duplicate(field)) => Some(field),
+            _ => None,
+        };
+
+        match groups
+            .iter_mut()
+            .find(|group| field.map_or(false, |field| group.contains(&field)))
+        {
+            Some(group) => group.push(name),
+            None => groups.push(vec![name]),
+        }
+    }
+
+    Ok(groups)
+}
+
+/// Fields of the struct `T` deserializes from that cannot be left out, each with all the
+/// names it accepts, its own one first.
+///
+/// Only top-level fields are considered, nested values are deserialized from placeholders.
+pub(crate) fn required_fields<T: DeserializeOwned>() -> Result<Vec<Vec<&'static str>>> {
+    let groups = field_names::<T>()?;
+
+    Ok(groups
+        .iter()
+        .filter(|&group| {
+            let field = group[0];
+            let names = groups
+                .iter()
+                .map(|group| group[0])
+                .filter(|&name| name != field)
+                .collect();
+
+            matches!(T::deserialize(Probe { names }), Err(ProbeError::Missing(missing)) if missing == field)
+        })
+        .cloned()
+        .collect())
+}
+
+#[derive(Debug)]
+enum ProbeError {
+    Struct(&'static str, &'static [&'static str]),
+    Missing(&'static str),
+    Duplicate(&'static str),
+    Other(String),
+}
+
+impl fmt::Display for ProbeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ProbeError::Struct(name, _) => write!(f, "struct {}", name),
+            ProbeError::Missing(field) => write!(f, "missing field `{}`", field),
+            ProbeError::Duplicate(field) => write!(f, "duplicate field `{}`", field),
+            ProbeError::Other(ref message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for ProbeError {}
+
+impl de::Error for ProbeError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        ProbeError::Other(msg.to_string())
+    }
+
+    fn missing_field(field: &'static str) -> Self {
+        ProbeError::Missing(field)
+    }
+
+    fn duplicate_field(field: &'static str) -> Self {
+        ProbeError::Duplicate(field)
+    }
+}
+
+type ProbeResult<T> = std::result::Result<T, ProbeError>;
+
+/// Stops at the first struct, reporting its name and fields.
+struct Recorder;
+
+impl<'de> de::Deserializer<'de> for Recorder {
+    type Error = ProbeError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> ProbeResult<V::Value> {
+        Err(ProbeError::Other("not a struct".into()))
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        _visitor: V,
+    ) -> ProbeResult<V::Value> {
+        Err(ProbeError::Struct(name, fields))
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map enum identifier ignored_any
+    }
+}
+
+/// A struct with placeholders for the fields `names`.
+struct Probe {
+    names: Vec<&'static str>,
+}
+
+impl<'de> de::Deserializer<'de> for Probe {
+    type Error = ProbeError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> ProbeResult<V::Value> {
+        Err(ProbeError::Other("not a struct".into()))
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> ProbeResult<V::Value> {
+        visitor.visit_map(Fields::new(self.names.into_iter()))
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map enum identifier ignored_any
+    }
+}
+
+/// A neutral value for whatever type is requested.
+struct Placeholder;
+
+impl<'de> de::Deserializer<'de> for Placeholder {
+    type Error = ProbeError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> ProbeResult<V::Value> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> ProbeResult<V::Value> {
+        visitor.visit_bool(false)
+    }
+
+    fn deserialize_i8<V: Visitor<'de>>(self, visitor: V) -> ProbeResult<V::Value> {
+        visitor.visit_i8(0)
+    }
+
+    fn deserialize_i16<V: Visitor<'de>>(self, visitor: V) -> ProbeResult<V::Value> {
+        visitor.visit_i16(0)
+    }
+
+    fn deserialize_i32<V: Visitor<'de>>(self, visitor: V) -> ProbeResult<V::Value> {
+        visitor.visit_i32(0)
+    }
+
+    fn deserialize_i64<V: Visitor<'de>>(self, visitor: V) -> ProbeResult<V::Value> {
+        visitor.visit_i64(0)
+    }
+
+    fn deserialize_u8<V: Visitor<'de>>(self, visitor: V) -> ProbeResult<V::Value> {
+        visitor.visit_u8(0)
+    }
+
+    fn deserialize_u16<V: Visitor<'de>>(self, visitor: V) -> ProbeResult<V::Value> {
+        visitor.visit_u16(0)
+    }
+
+    fn deserialize_u32<V: Visitor<'de>>(self, visitor: V) -> ProbeResult<V::Value> {
+        visitor.visit_u32(0)
+    }
+
+    fn deserialize_u64<V: Visitor<'de>>(self, visitor: V) -> ProbeResult<V::Value> {
+        visitor.visit_u64(0)
+    }
+
+    fn deserialize_f32<V: Visitor<'de>>(self, visitor: V) -> ProbeResult<V::Value> {
+        visitor.visit_f32(0.0)
+    }
+
+    fn deserialize_f64<V: Visitor<'de>>(self, visitor: V) -> ProbeResult<V::Value> {
+        visitor.visit_f64(0.0)
+    }
+
+    fn deserialize_char<V: Visitor<'de>>(self, visitor: V) -> ProbeResult<V::Value> {
+        visitor.visit_char('\0')
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> ProbeResult<V::Value> {
+        visitor.visit_str("")
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> ProbeResult<V::Value> {
+        visitor.visit_str("")
+    }
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> ProbeResult<V::Value> {
+        visitor.visit_bytes(&[])
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> ProbeResult<V::Value> {
+        visitor.visit_bytes(&[])
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> ProbeResult<V::Value> {
+        visitor.visit_none()
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> ProbeResult<V::Value> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> ProbeResult<V::Value> {
+        visitor.visit_seq(Elements(0))
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(self, len: usize, visitor: V) -> ProbeResult<V::Value> {
+        visitor.visit_seq(Elements(len))
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> ProbeResult<V::Value> {
+        visitor.visit_seq(Elements(len))
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> ProbeResult<V::Value> {
+        let fields: &'static [&'static str] = &[];
+        visitor.visit_map(Fields::new(fields.iter().copied()))
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> ProbeResult<V::Value> {
+        visitor.visit_map(Fields::new(fields.iter().copied()))
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> ProbeResult<V::Value> {
+        match variants.first() {
+            Some(&variant) => visitor.visit_enum(Variant(variant)),
+            None => Err(ProbeError::Other("enum without variants".into())),
+        }
+    }
+
+    forward_to_deserialize_any! {
+        i128 u128 unit unit_struct identifier ignored_any
+    }
+}
+
+/// Sequence of `len` placeholders.
+struct Elements(usize);
+
+impl<'de> de::SeqAccess<'de> for Elements {
+    type Error = ProbeError;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> ProbeResult<Option<T::Value>>
+    where
+        T: de::DeserializeSeed<'de>,
+    {
+        if self.0 == 0 {
+            return Ok(None);
+        }
+
+        self.0 -= 1;
+        seed.deserialize(Placeholder).map(Some)
+    }
+}
+
+/// Map of the given fields to placeholders.
+struct Fields<I> {
+    fields: I,
+}
+
+impl<I> Fields<I> {
+    fn new(fields: I) -> Self {
+        Fields { fields }
+    }
+}
+
+impl<'de, I> de::MapAccess<'de> for Fields<I>
+where
+    I: Iterator<Item = &'static str>,
+{
+    type Error = ProbeError;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> ProbeResult<Option<K::Value>>
+    where
+        K: de::DeserializeSeed<'de>,
+    {
+        match self.fields.next() {
+            Some(field) => seed.deserialize(field.into_deserializer()).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> ProbeResult<V::Value>
+    where
+        V: de::DeserializeSeed<'de>,
+    {
+        seed.deserialize(Placeholder)
+    }
+}
+
+/// The first variant of an enum, with placeholders for its content.
+struct Variant(&'static str);
+
+impl<'de> de::EnumAccess<'de> for Variant {
+    type Error = ProbeError;
+    type Variant = Self;
+
+    fn variant_seed<V>(self, seed: V) -> ProbeResult<(V::Value, Self)>
+    where
+        V: de::DeserializeSeed<'de>,
+    {
+        let variant = seed.deserialize(self.0.into_deserializer())?;
+        Ok((variant, self))
+    }
+}
+
+impl<'de> de::VariantAccess<'de> for Variant {
+    type Error = ProbeError;
+
+    fn unit_variant(self) -> ProbeResult<()> {
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> ProbeResult<T::Value>
+    where
+        T: de::DeserializeSeed<'de>,
+    {
+        seed.deserialize(Placeholder)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, len: usize, visitor: V) -> ProbeResult<V::Value> {
+        visitor.visit_seq(Elements(len))
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> ProbeResult<V::Value> {
+        visitor.visit_map(Fields::new(fields.iter().copied()))
+    }
+}
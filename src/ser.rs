@@ -3,7 +3,7 @@ use std::fmt::Display;
 use serde::ser;
 
 use crate::error::*;
-use crate::value::{Value, ValueKind};
+use crate::value::{Table, Value, ValueKind};
 use crate::Config;
 
 #[derive(Default, Debug)]
@@ -695,9 +695,351 @@ impl ser::SerializeStructVariant for StringKeySerializer {
     }
 }
 
+/// Serializes into a [`Value`] tree, the inverse of deserializing from a `Value`.
+///
+/// Structs and maps become tables, sequences and tuples arrays, and `None` and units nil.
+/// Enums are externally tagged: unit variants become their name and other variants a table
+/// with the variant name as only key.
+pub(crate) struct ValueSerializer;
+
+impl ser::Serializer for ValueSerializer {
+    type Ok = Value;
+    type Error = ConfigError;
+    type SerializeSeq = SeqSerializer;
+    type SerializeTuple = SeqSerializer;
+    type SerializeTupleStruct = SeqSerializer;
+    type SerializeTupleVariant = VariantSerializer<SeqSerializer>;
+    type SerializeMap = TableSerializer;
+    type SerializeStruct = TableSerializer;
+    type SerializeStructVariant = VariantSerializer<TableSerializer>;
+
+    fn serialize_bool(self, v: bool) -> Result<Self::Ok> {
+        Ok(v.into())
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<Self::Ok> {
+        self.serialize_i64(v as i64)
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<Self::Ok> {
+        self.serialize_i64(v as i64)
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<Self::Ok> {
+        self.serialize_i64(v as i64)
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<Self::Ok> {
+        Ok(v.into())
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<Self::Ok> {
+        self.serialize_i64(v as i64)
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<Self::Ok> {
+        self.serialize_i64(v as i64)
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<Self::Ok> {
+        self.serialize_i64(v as i64)
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<Self::Ok> {
+        if v > (i64::MAX as u64) {
+            Err(ConfigError::Message(format!(
+                "value {} is greater than the max {}",
+                v,
+                i64::MAX
+            )))
+        } else {
+            self.serialize_i64(v as i64)
+        }
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<Self::Ok> {
+        self.serialize_f64(v as f64)
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<Self::Ok> {
+        Ok(v.into())
+    }
+
+    fn serialize_char(self, v: char) -> Result<Self::Ok> {
+        Ok(v.to_string().into())
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Self::Ok> {
+        Ok(v.into())
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<Self::Ok> {
+        Ok(v.iter().map(|&byte| byte as i64).collect::<Vec<_>>().into())
+    }
+
+    fn serialize_none(self) -> Result<Self::Ok> {
+        self.serialize_unit()
+    }
+
+    fn serialize_some<T>(self, value: &T) -> Result<Self::Ok>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Self::Ok> {
+        Ok(Value::from(ValueKind::Nil))
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Self::Ok> {
+        self.serialize_unit()
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<Self::Ok> {
+        self.serialize_str(variant)
+    }
+
+    fn serialize_newtype_struct<T>(self, _name: &'static str, value: &T) -> Result<Self::Ok>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        let mut table = Table::new();
+        table.insert(variant.to_string(), value.serialize(self)?);
+        Ok(table.into())
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq> {
+        Ok(SeqSerializer {
+            values: Vec::with_capacity(len.unwrap_or(0)),
+        })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleVariant> {
+        Ok(VariantSerializer {
+            variant,
+            inner: self.serialize_seq(Some(len))?,
+        })
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap> {
+        Ok(TableSerializer {
+            table: Table::new(),
+            key: None,
+        })
+    }
+
+    fn serialize_struct(self, _name: &'static str, len: usize) -> Result<Self::SerializeStruct> {
+        self.serialize_map(Some(len))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStructVariant> {
+        Ok(VariantSerializer {
+            variant,
+            inner: self.serialize_map(Some(len))?,
+        })
+    }
+}
+
+pub(crate) struct SeqSerializer {
+    values: Vec<Value>,
+}
+
+impl ser::SerializeSeq for SeqSerializer {
+    type Ok = Value;
+    type Error = ConfigError;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        self.values.push(value.serialize(ValueSerializer)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Self::Ok> {
+        Ok(self.values.into())
+    }
+}
+
+impl ser::SerializeTuple for SeqSerializer {
+    type Ok = Value;
+    type Error = ConfigError;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<Self::Ok> {
+        ser::SerializeSeq::end(self)
+    }
+}
+
+impl ser::SerializeTupleStruct for SeqSerializer {
+    type Ok = Value;
+    type Error = ConfigError;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<Self::Ok> {
+        ser::SerializeSeq::end(self)
+    }
+}
+
+pub(crate) struct TableSerializer {
+    table: Table,
+    key: Option<String>,
+}
+
+impl ser::SerializeMap for TableSerializer {
+    type Ok = Value;
+    type Error = ConfigError;
+
+    fn serialize_key<T>(&mut self, key: &T) -> Result<()>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        self.key = Some(key.serialize(StringKeySerializer)?);
+        Ok(())
+    }
+
+    fn serialize_value<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        let key = self
+            .key
+            .take()
+            .ok_or_else(|| ConfigError::Message("map value without a key".to_string()))?;
+        self.table.insert(key, value.serialize(ValueSerializer)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Self::Ok> {
+        Ok(self.table.into())
+    }
+}
+
+impl ser::SerializeStruct for TableSerializer {
+    type Ok = Value;
+    type Error = ConfigError;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<()>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        self.table
+            .insert(key.to_string(), value.serialize(ValueSerializer)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Self::Ok> {
+        ser::SerializeMap::end(self)
+    }
+}
+
+/// Wraps the content of an enum variant in a table keyed by the variant name.
+pub(crate) struct VariantSerializer<S> {
+    variant: &'static str,
+    inner: S,
+}
+
+impl<S> VariantSerializer<S> {
+    fn wrap(variant: &'static str, value: Value) -> Value {
+        let mut table = Table::new();
+        table.insert(variant.to_string(), value);
+        table.into()
+    }
+}
+
+impl ser::SerializeTupleVariant for VariantSerializer<SeqSerializer> {
+    type Ok = Value;
+    type Error = ConfigError;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        ser::SerializeSeq::serialize_element(&mut self.inner, value)
+    }
+
+    fn end(self) -> Result<Self::Ok> {
+        let value = ser::SerializeSeq::end(self.inner)?;
+        Ok(Self::wrap(self.variant, value))
+    }
+}
+
+impl ser::SerializeStructVariant for VariantSerializer<TableSerializer> {
+    type Ok = Value;
+    type Error = ConfigError;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<()>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        ser::SerializeStruct::serialize_field(&mut self.inner, key, value)
+    }
+
+    fn end(self) -> Result<Self::Ok> {
+        let value = ser::SerializeMap::end(self.inner)?;
+        Ok(Self::wrap(self.variant, value))
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
+    use serde::Deserialize;
 
     #[test]
     fn test_struct() {
@@ -716,4 +1058,81 @@ mod test {
         let actual: Test = config.try_into().unwrap();
         assert_eq!(test, actual);
     }
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    enum Mode {
+        Off,
+        Fixed(u32),
+        Range(u32, u32),
+        Custom { name: String, level: i8 },
+    }
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    struct Unit;
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    struct Newtype(String);
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    struct Everything {
+        boolean: bool,
+        small: i8,
+        unsigned: u64,
+        float: f32,
+        character: char,
+        string: String,
+        newtype: Newtype,
+        unit: Unit,
+        some: Option<i64>,
+        none: Option<i64>,
+        tuple: (i32, String),
+        seq: Vec<Vec<u8>>,
+        map: std::collections::BTreeMap<String, f64>,
+        modes: Vec<Mode>,
+    }
+
+    #[test]
+    fn test_value_round_trip() {
+        let mut map = std::collections::BTreeMap::new();
+        map.insert("ratio".to_string(), 2.5);
+
+        let test = Everything {
+            boolean: true,
+            small: -3,
+            unsigned: 42,
+            float: 0.5,
+            character: 'x',
+            string: "hello".to_string(),
+            newtype: Newtype("inner".to_string()),
+            unit: Unit,
+            some: Some(7),
+            none: None,
+            tuple: (1, "one".to_string()),
+            seq: vec![vec![1, 2], vec![]],
+            map,
+            modes: vec![
+                Mode::Off,
+                Mode::Fixed(3),
+                Mode::Range(1, 5),
+                Mode::Custom {
+                    name: "turbo".to_string(),
+                    level: -1,
+                },
+            ],
+        };
+
+        let value = Value::try_from(&test).unwrap();
+        let table = value.clone().into_table().unwrap();
+        assert_eq!(table["none"].kind, ValueKind::Nil);
+        assert_eq!(table["unit"].kind, ValueKind::Nil);
+        assert_eq!(table["character"].kind, ValueKind::String("x".into()));
+
+        let actual = Everything::deserialize(value).unwrap();
+        assert_eq!(test, actual);
+    }
+
+    #[test]
+    fn test_value_u64_overflow() {
+        assert!(Value::try_from(&u64::MAX).is_err());
+    }
 }
@@ -0,0 +1,59 @@
+use crate::error::*;
+use crate::map::Map;
+use crate::source::Source;
+use crate::value::Value;
+
+/// A source wrapping a value of one of the supported formats that is already in memory,
+/// such as a `serde_json::Value` or a `toml::Value`.
+///
+/// This avoids serializing the value to a string only for it to be parsed again.
+/// The wrapped value must be a table (an object, for JSON).
+#[derive(Clone, Debug)]
+pub struct SerdeValue<T> {
+    value: T,
+
+    /// Name reported as the origin of values and errors.
+    origin: Option<String>,
+}
+
+impl<T> SerdeValue<T> {
+    pub fn new(value: T) -> Self {
+        SerdeValue {
+            value,
+            origin: None,
+        }
+    }
+
+    /// Label the value with a name that is reported as its origin.
+    pub fn origin(mut self, origin: &str) -> Self {
+        self.origin = Some(origin.into());
+        self
+    }
+}
+
+#[cfg(feature = "json")]
+impl Source for SerdeValue<serde_json::Value> {
+    fn clone_into_box(&self) -> Box<dyn Source + Send + Sync> {
+        Box::new((*self).clone())
+    }
+
+    fn collect(&self) -> Result<Map<String, Value>> {
+        crate::file::format::json::from_json_value(self.origin.as_ref(), &self.value).into_table()
+    }
+}
+
+#[cfg(feature = "toml")]
+impl Source for SerdeValue<toml::Value> {
+    fn clone_into_box(&self) -> Box<dyn Source + Send + Sync> {
+        Box::new((*self).clone())
+    }
+
+    fn collect(&self) -> Result<Map<String, Value>> {
+        crate::file::format::toml::from_toml_value(
+            self.origin.as_ref(),
+            &self.value,
+            Default::default(),
+        )
+        .into_table()
+    }
+}
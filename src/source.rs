@@ -1,12 +1,22 @@
 use std::fmt::Debug;
 use std::str::FromStr;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::{Arc, Mutex};
 
 use async_trait::async_trait;
 
+use crate::env::{parse_scalar, Environment};
 use crate::error::*;
+use crate::file::format::ALL_EXTENSIONS;
+use crate::file::File;
 use crate::map::Map;
 use crate::path;
-use crate::value::{Value, ValueKind};
+use crate::value::{FloatPolicy, Value, ValueKind};
+
+#[cfg(feature = "archive")]
+pub use crate::file::{Archive, ArchiveFormat};
+#[cfg(any(feature = "json", feature = "toml"))]
+pub use crate::serde_value::SerdeValue;
 
 /// Describes a generic _source_ of configuration properties.
 pub trait Source: Debug {
@@ -24,6 +34,37 @@ pub trait Source: Debug {
 
         Ok(())
     }
+
+    /// Keys this source provides, if they can be known without collecting it.
+    ///
+    /// Keys are paths such as `database.url`; a key also provides all the keys nested in it.
+    /// Returns `None` by default, when the keys are unknown. Used by
+    /// [`ConfigBuilder::check_required`](crate::ConfigBuilder::check_required).
+    fn known_keys(&self) -> Option<Vec<String>> {
+        None
+    }
+
+    /// Comments attached to the keys of this source, by path such as `database.url`.
+    ///
+    /// Returns no comments by default. Collected when building a configuration and exposed
+    /// by [`Config::comment`](crate::Config::comment).
+    fn comments(&self) -> Result<Map<String, String>> {
+        Ok(Map::new())
+    }
+}
+
+/// Keys of all the `sources`, if they are all known.
+fn all_known_keys<'a, T>(sources: impl IntoIterator<Item = &'a T>) -> Option<Vec<String>>
+where
+    T: Source + ?Sized + 'a,
+{
+    let mut keys = Vec::new();
+
+    for source in sources {
+        keys.extend(source.known_keys()?);
+    }
+
+    Some(keys)
 }
 
 fn set_value(cache: &mut Value, key: &str, value: &Value) {
@@ -36,6 +77,353 @@ fn set_value(cache: &mut Value, key: &str, value: &Value) {
     }
 }
 
+/// A source mounting all the values of another source under a key.
+///
+/// With the prefix `database`, a key `url` of the inner source is merged as `database.url`.
+/// The prefix is a path such as `services.database` and may contain subscripts; it is parsed
+/// when the source is collected, failing if it is not a valid path.
+#[derive(Clone, Debug)]
+pub struct Prefixed {
+    inner: Box<dyn Source + Send + Sync>,
+    prefix: String,
+}
+
+impl Prefixed {
+    pub fn new<S>(inner: S, prefix: &str) -> Self
+    where
+        S: Source + Send + Sync + 'static,
+    {
+        Prefixed {
+            inner: Box::new(inner),
+            prefix: prefix.into(),
+        }
+    }
+}
+
+impl Source for Prefixed {
+    fn clone_into_box(&self) -> Box<dyn Source + Send + Sync> {
+        Box::new((*self).clone())
+    }
+
+    fn collect(&self) -> Result<Map<String, Value>> {
+        let expr = path::Expression::from_str(&self.prefix)?;
+
+        let mut inner: Value = Map::<String, Value>::new().into();
+        self.inner.collect_to(&mut inner)?;
+
+        let mut cache: Value = Map::<String, Value>::new().into();
+        expr.set(&mut cache, inner);
+
+        cache.into_table()
+    }
+
+    fn known_keys(&self) -> Option<Vec<String>> {
+        let keys = self.inner.known_keys()?;
+
+        Some(
+            keys.iter()
+                .map(|key| format!("{}.{}", self.prefix, key))
+                .collect(),
+        )
+    }
+
+    fn comments(&self) -> Result<Map<String, String>> {
+        Ok(self
+            .inner
+            .comments()?
+            .into_iter()
+            .map(|(key, comment)| (format!("{}.{}", self.prefix, key), comment))
+            .collect())
+    }
+}
+
+/// A source using the values of a `primary` source, or those of a `secondary` one when the
+/// primary fails or is empty.
+///
+/// Unlike adding both sources to a builder, the values are not merged: only one of the two
+/// sources is used. The primary is empty when it is collected without error but yields no key
+/// at all; a key set to nil is not empty. If the secondary source fails too, its error is
+/// returned.
+///
+/// Any error of the primary falls back, including a parse error in an existing file, so check
+/// [`primary_error`](Self::primary_error) to report it rather than silently using the secondary.
+#[derive(Clone, Debug)]
+pub struct Fallback {
+    primary: Box<dyn Source + Send + Sync>,
+    secondary: Box<dyn Source + Send + Sync>,
+
+    /// Source used by the last collection, shared with the clones of this source
+    used: Arc<AtomicU8>,
+
+    /// Error of the primary in the last collection, shared like `used`
+    primary_error: Arc<Mutex<Option<Arc<ConfigError>>>>,
+}
+
+const UNUSED: u8 = 0;
+const PRIMARY: u8 = 1;
+const SECONDARY: u8 = 2;
+
+impl Fallback {
+    pub fn new<P, S>(primary: P, secondary: S) -> Self
+    where
+        P: Source + Send + Sync + 'static,
+        S: Source + Send + Sync + 'static,
+    {
+        Fallback {
+            primary: Box::new(primary),
+            secondary: Box::new(secondary),
+            used: Arc::new(AtomicU8::new(UNUSED)),
+            primary_error: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Whether the last collection used the secondary source, `None` if it was never collected.
+    ///
+    /// Clones of this source, like the one kept by a builder, share this record, so it can be
+    /// checked once the configuration is built.
+    pub fn used_secondary(&self) -> Option<bool> {
+        match self.used.load(Ordering::SeqCst) {
+            PRIMARY => Some(false),
+            SECONDARY => Some(true),
+            _ => None,
+        }
+    }
+
+    /// The error of the primary source if it failed in the last collection, making it fall back
+    /// to the secondary.
+    ///
+    /// `None` if the primary was used, was empty, or was never collected.
+    pub fn primary_error(&self) -> Option<Arc<ConfigError>> {
+        self.primary_error
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .clone()
+    }
+}
+
+impl Source for Fallback {
+    fn clone_into_box(&self) -> Box<dyn Source + Send + Sync> {
+        Box::new((*self).clone())
+    }
+
+    fn collect(&self) -> Result<Map<String, Value>> {
+        let collected = self.primary.collect();
+        let mut primary_error = self
+            .primary_error
+            .lock()
+            .unwrap_or_else(|err| err.into_inner());
+
+        match collected {
+            Ok(map) if !map.is_empty() => {
+                *primary_error = None;
+                self.used.store(PRIMARY, Ordering::SeqCst);
+                Ok(map)
+            }
+
+            collected => {
+                *primary_error = collected.err().map(Arc::new);
+                self.used.store(SECONDARY, Ordering::SeqCst);
+                self.secondary.collect()
+            }
+        }
+    }
+
+    fn comments(&self) -> Result<Map<String, String>> {
+        match self.used_secondary() {
+            Some(true) => self.secondary.comments(),
+            Some(false) => self.primary.comments(),
+            None => Ok(Map::new()),
+        }
+    }
+}
+
+/// A key-value store read by [`KeyValue`], such as an embedded database.
+pub trait KvBackend {
+    /// All the pairs whose key starts with `prefix`.
+    fn scan_prefix(&self, prefix: &str) -> Vec<(String, String)>;
+}
+
+/// A source reading the pairs of a [`KvBackend`] whose key starts with a prefix.
+///
+/// The prefix is removed from the keys, which are then nested like those of an
+/// [`Environment`]: with a prefix of `app/` and a separator of `/`, the
+/// key `app/database/url` sets `database.url`. Values are strings unless
+/// [`try_parsing`](Self::try_parsing) is enabled.
+pub struct KeyValue<S> {
+    backend: Arc<S>,
+    prefix: String,
+
+    /// Separator replaced with `.` in keys, if any
+    separator: Option<String>,
+
+    /// Parse booleans, integers and floats
+    try_parsing: bool,
+}
+
+impl<S: KvBackend> KeyValue<S> {
+    pub fn new(backend: S, prefix: &str) -> Self {
+        KeyValue {
+            backend: Arc::new(backend),
+            prefix: prefix.into(),
+            separator: None,
+            try_parsing: false,
+        }
+    }
+
+    /// Nest keys on `separator`, e.g. `/` for keys such as `database/url`.
+    pub fn separator(mut self, separator: &str) -> Self {
+        self.separator = Some(separator.into());
+        self
+    }
+
+    /// Parse values that are booleans, integers or floats instead of keeping them as strings.
+    pub fn try_parsing(mut self, try_parsing: bool) -> Self {
+        self.try_parsing = try_parsing;
+        self
+    }
+
+    fn parse_value(&self, value: String) -> ValueKind {
+        if !self.try_parsing {
+            return ValueKind::String(value);
+        }
+
+        parse_scalar(value, &[], false, FloatPolicy::Allow)
+    }
+}
+
+impl<S> Clone for KeyValue<S> {
+    fn clone(&self) -> Self {
+        KeyValue {
+            backend: self.backend.clone(),
+            prefix: self.prefix.clone(),
+            separator: self.separator.clone(),
+            try_parsing: self.try_parsing,
+        }
+    }
+}
+
+impl<S> Debug for KeyValue<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("KeyValue")
+            .field("prefix", &self.prefix)
+            .field("separator", &self.separator)
+            .field("try_parsing", &self.try_parsing)
+            .finish()
+    }
+}
+
+impl<S: KvBackend + Send + Sync + 'static> Source for KeyValue<S> {
+    fn clone_into_box(&self) -> Box<dyn Source + Send + Sync> {
+        Box::new((*self).clone())
+    }
+
+    fn collect(&self) -> Result<Map<String, Value>> {
+        let uri: String = "the key-value store".into();
+
+        Ok(self
+            .backend
+            .scan_prefix(&self.prefix)
+            .into_iter()
+            .filter_map(|(key, value)| {
+                let key = key.strip_prefix(self.prefix.as_str())?;
+                if key.is_empty() {
+                    return None;
+                }
+
+                let key = match self.separator {
+                    Some(ref separator) => key.replace(separator.as_str(), "."),
+                    None => key.to_string(),
+                };
+
+                Some((key, Value::new(Some(&uri), self.parse_value(value))))
+            })
+            .collect())
+    }
+}
+
+/// Creates the source described by `uri`, to wire sources from strings such as the items of a
+/// comma-separated variable.
+///
+/// The supported schemes are:
+///
+/// - `file://`, for a required [`File`] at the path following it, e.g. `file://config/app.toml`
+///   or `file:///etc/app.toml`. Its format is the one of its extension, unless given with a
+///   `format` parameter, e.g. `file://config/app?format=yaml`.
+/// - `env://`, for an [`Environment`] with the prefix following it, if any, e.g. `env://APP_`
+///   for the variables starting with `APP_`. A `separator` parameter sets its separator, which
+///   also follows the prefix, e.g. `env://APP?separator=__` for `APP__DATABASE__URL`.
+///
+/// Any other scheme, such as `http://`, is an error. Fetching remote configuration needs an
+/// HTTP client and usually an async runtime, which this crate leaves to [`AsyncSource`]
+/// implementations. Several sources collect as one when put in a `Vec`, which
+/// can then be added to a builder.
+pub fn from_uri(uri: &str) -> Result<Box<dyn Source + Send + Sync>> {
+    let invalid =
+        |reason: String| ConfigError::Message(format!("invalid source URI {:?}: {}", uri, reason));
+
+    let (scheme, rest) = split_pair(uri, "://")
+        .ok_or_else(|| invalid("expected a scheme such as `file://`".into()))?;
+    let (location, query) = split_pair(rest, "?").unwrap_or((rest, ""));
+
+    let mut params = Map::new();
+    for pair in query.split('&').filter(|pair| !pair.is_empty()) {
+        let (name, value) = split_pair(pair, "=").unwrap_or((pair, ""));
+        params.insert(name, value);
+    }
+
+    let mut param = |name: &str| params.remove(name);
+
+    let source: Box<dyn Source + Send + Sync> = match scheme {
+        "file" => {
+            if location.is_empty() {
+                return Err(invalid("expected a path".into()));
+            }
+
+            match param("format") {
+                Some(name) => {
+                    let format = ALL_EXTENSIONS
+                        .iter()
+                        .find(|(_, extensions)| extensions.contains(&name))
+                        .map(|(format, _)| *format)
+                        .ok_or_else(|| invalid(format!("unknown format {:?}", name)))?;
+                    Box::new(File::new(location, format))
+                }
+                None => Box::new(File::with_name(location)),
+            }
+        }
+
+        "env" => {
+            let mut env = match location.trim_end_matches('_') {
+                "" => Environment::new(),
+                prefix => Environment::with_prefix(prefix),
+            };
+            if let Some(separator) = param("separator") {
+                env = env.separator(separator);
+            }
+            Box::new(env)
+        }
+
+        _ => {
+            return Err(invalid(format!(
+                "unsupported scheme {:?}, expected `file` or `env`",
+                scheme
+            )))
+        }
+    };
+
+    if let Some(name) = params.keys().next() {
+        return Err(invalid(format!("unknown parameter {:?}", name)));
+    }
+
+    Ok(source)
+}
+
+/// `s` split around the first `delimiter`, if it contains one.
+fn split_pair<'a>(s: &'a str, delimiter: &str) -> Option<(&'a str, &'a str)> {
+    let mut parts = s.splitn(2, delimiter);
+    Some((parts.next()?, parts.next()?))
+}
+
 /// Describes a generic _source_ of configuration properties capable of using an async runtime.
 ///
 /// At the moment this library does not implement it, although it allows using its implementations
@@ -99,6 +487,10 @@ impl Source for Vec<Box<dyn Source + Send + Sync>> {
             unreachable!();
         }
     }
+
+    fn known_keys(&self) -> Option<Vec<String>> {
+        all_known_keys(self.iter().map(|source| source.as_ref()))
+    }
 }
 
 impl Source for [Box<dyn Source + Send + Sync>] {
@@ -119,6 +511,10 @@ impl Source for [Box<dyn Source + Send + Sync>] {
             unreachable!();
         }
     }
+
+    fn known_keys(&self) -> Option<Vec<String>> {
+        all_known_keys(self.iter().map(|source| source.as_ref()))
+    }
 }
 
 impl<T> Source for Vec<T>
@@ -144,4 +540,8 @@ where
             unreachable!();
         }
     }
+
+    fn known_keys(&self) -> Option<Vec<String>> {
+        all_known_keys(self)
+    }
 }
@@ -0,0 +1,148 @@
+//! Parsing of durations and byte sizes, given as plain numbers or with a unit suffix.
+
+use std::time::Duration;
+
+use serde::de::{Deserialize, Deserializer, Error as _};
+
+use crate::error::*;
+use crate::value::{Value, ValueKind};
+
+/// A [`Duration`] deserialized like [`Config::get_duration`](crate::Config::get_duration)
+/// reads it, from a number of seconds or a string such as `"30s"`.
+///
+/// Unlike `Duration` itself, this works anywhere in a deserialized tree, e.g. in a
+/// `Vec<HumanDuration>` for `timeouts = ["1s", "500ms"]` or as a field of a nested struct.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct HumanDuration(pub Duration);
+
+impl From<HumanDuration> for Duration {
+    fn from(duration: HumanDuration) -> Self {
+        duration.0
+    }
+}
+
+impl<'de> Deserialize<'de> for HumanDuration {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        into_duration(Value::deserialize(deserializer)?)
+            .map(HumanDuration)
+            .map_err(D::Error::custom)
+    }
+}
+
+/// A size in bytes deserialized like [`Config::get_bytes`](crate::Config::get_bytes) reads
+/// it, from a number of bytes or a string such as `"10MB"`.
+///
+/// This works anywhere in a deserialized tree, e.g. as a field of a nested struct or as the
+/// values of a map.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct ByteSize(pub u64);
+
+impl From<ByteSize> for u64 {
+    fn from(size: ByteSize) -> Self {
+        size.0
+    }
+}
+
+impl<'de> Deserialize<'de> for ByteSize {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        into_bytes(Value::deserialize(deserializer)?)
+            .map(ByteSize)
+            .map_err(D::Error::custom)
+    }
+}
+
+const DURATION_UNITS: &[(&str, f64)] = &[
+    ("ns", 1e-9),
+    ("us", 1e-6),
+    ("ms", 1e-3),
+    ("s", 1.0),
+    ("m", 60.0),
+    ("h", 3600.0),
+    ("d", 86400.0),
+];
+
+const BYTE_UNITS: &[(&str, f64)] = &[
+    ("b", 1.0),
+    ("kb", 1e3),
+    ("mb", 1e6),
+    ("gb", 1e9),
+    ("tb", 1e12),
+    ("kib", 1024.0),
+    ("mib", 1_048_576.0),
+    ("gib", 1_073_741_824.0),
+    ("tib", 1_099_511_627_776.0),
+];
+
+/// Converts a number of seconds or a string like `"30s"` or `"1.5h"` to a [`Duration`].
+pub(crate) fn into_duration(value: Value) -> Result<Duration> {
+    const EXPECTED: &str = "a duration such as 30 or \"30s\"";
+
+    let seconds = into_quantity(value, DURATION_UNITS, "s", EXPECTED)?;
+    Ok(Duration::from_secs_f64(seconds))
+}
+
+/// Converts a number of bytes or a string like `"10MB"` or `"4KiB"` to a number of bytes.
+pub(crate) fn into_bytes(value: Value) -> Result<u64> {
+    const EXPECTED: &str = "a size in bytes such as 1024 or \"10MB\"";
+
+    let bytes = into_quantity(value, BYTE_UNITS, "b", EXPECTED)?;
+    Ok(bytes.round() as u64)
+}
+
+fn into_quantity(
+    value: Value,
+    units: &[(&str, f64)],
+    default_unit: &str,
+    expected: &'static str,
+) -> Result<f64> {
+    let origin = value.origin().map(String::from);
+
+    let (quantity, unexpected) = match value.kind {
+        ValueKind::Integer(n) => (n as f64, Unexpected::Integer(n)),
+        ValueKind::Float(n) => (n, Unexpected::Float(n)),
+
+        ValueKind::String(s) => match parse_quantity(&s, units, default_unit) {
+            Some(quantity) => (quantity, Unexpected::Str(s)),
+            None => {
+                return Err(ConfigError::invalid_type(
+                    origin,
+                    Unexpected::Str(s),
+                    expected,
+                ))
+            }
+        },
+
+        ValueKind::Number(n) => (-1.0, Unexpected::Number(n)),
+        ValueKind::Boolean(b) => (-1.0, Unexpected::Bool(b)),
+        ValueKind::Nil => (-1.0, Unexpected::Unit),
+        ValueKind::Table(_) => (-1.0, Unexpected::Map),
+        ValueKind::Array(_) => (-1.0, Unexpected::Seq),
+    };
+
+    // Also rejects NaN
+    if quantity >= 0.0 && quantity < u64::MAX as f64 {
+        Ok(quantity)
+    } else {
+        Err(ConfigError::invalid_type(origin, unexpected, expected))
+    }
+}
+
+/// Parses a number followed by an optional unit, in the default unit if there is none.
+fn parse_quantity(text: &str, units: &[(&str, f64)], default_unit: &str) -> Option<f64> {
+    let text = text.trim();
+    let split = text
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(text.len());
+    let (number, unit) = text.split_at(split);
+
+    let number: f64 = number.parse().ok()?;
+    let unit = match unit.trim() {
+        "" => default_unit.to_string(),
+        unit => unit.to_lowercase(),
+    };
+
+    units
+        .iter()
+        .find(|&&(name, _)| name == unit)
+        .map(|&(_, factor)| number * factor)
+}
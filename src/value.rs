@@ -2,9 +2,11 @@ use std::fmt;
 use std::fmt::Display;
 
 use serde::de::{Deserialize, Deserializer, Visitor};
+use serde::ser::Serialize;
 
 use crate::error::*;
 use crate::map::Map;
+use crate::ser::ValueSerializer;
 
 /// Underlying kind of the configuration value.
 ///
@@ -17,6 +19,15 @@ pub enum ValueKind {
     Boolean(bool),
     Integer(i64),
     Float(f64),
+
+    /// A number kept as written because it fits neither an `Integer` nor a `Float`, such as
+    /// `1e400` or an integer beyond the range of `i64`.
+    ///
+    /// Only produced by the JSON format with the `arbitrary_precision` feature. It converts to
+    /// the numeric types it fits in, fails on overflow, and deserializes as is into types
+    /// parsing their value from a string, such as big decimal types.
+    Number(String),
+
     String(String),
     Table(Table),
     Array(Array),
@@ -83,6 +94,18 @@ where
     }
 }
 
+// With `preserve_order` maps are `IndexMap`s, still allow building tables from a `HashMap`
+#[cfg(feature = "preserve_order")]
+impl<T> From<std::collections::HashMap<String, T>> for ValueKind
+where
+    T: Into<Value>,
+{
+    fn from(values: std::collections::HashMap<String, T>) -> Self {
+        let t = values.into_iter().map(|(k, v)| (k, v.into())).collect();
+        ValueKind::Table(t)
+    }
+}
+
 impl<T> From<Vec<T>> for ValueKind
 where
     T: Into<Value>,
@@ -99,6 +122,7 @@ impl Display for ValueKind {
             ValueKind::Boolean(value) => write!(f, "{}", value),
             ValueKind::Integer(value) => write!(f, "{}", value),
             ValueKind::Float(value) => write!(f, "{}", value),
+            ValueKind::Number(ref value) => write!(f, "{}", value),
             ValueKind::Nil => write!(f, "nil"),
             ValueKind::Table(ref table) => write!(f, "{{ {} }}", {
                 table
@@ -113,6 +137,25 @@ impl Display for ValueKind {
     }
 }
 
+/// How the non-finite floats `inf`, `-inf` and `NaN` are treated.
+///
+/// Parsing `"inf"` or `"nan"` as a float succeeds, so with [`FloatPolicy::Allow`] such strings
+/// can unexpectedly become floats, which formats like JSON cannot represent.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum FloatPolicy {
+    /// Non-finite floats are floats like any other.
+    Allow,
+
+    /// Non-finite floats are treated as strings, and are not accepted as floats.
+    Reject,
+}
+
+impl Default for FloatPolicy {
+    fn default() -> Self {
+        FloatPolicy::Allow
+    }
+}
+
 /// A configuration value.
 #[derive(Default, Debug, Clone, PartialEq)]
 pub struct Value {
@@ -150,7 +193,25 @@ impl Value {
         }
     }
 
+    /// Returns a description of the original location of the value, if known.
+    pub fn origin(&self) -> Option<&str> {
+        self.origin.as_deref()
+    }
+
+    /// Attempt to serialize a value from the given type.
+    ///
+    /// Structs and maps become tables, sequences and tuples arrays, and `None` and units nil.
+    pub fn try_from<T: Serialize>(from: &T) -> Result<Self> {
+        from.serialize(ValueSerializer)
+    }
+
     /// Attempt to deserialize this value into the requested type.
+    ///
+    /// A value does not know the configuration it comes from, so the deserialization options
+    /// of its builder, such as [`bytes_as_base64`](crate::ConfigBuilder::bytes_as_base64) or
+    /// [`float_policy`](crate::ConfigBuilder::float_policy), are not applied; the defaults are
+    /// used instead, as when deserializing a `Value` directly. Use [`Config::get`](crate::Config::get)
+    /// to apply them.
     pub fn try_into<'de, T: Deserialize<'de>>(self) -> Result<T> {
         T::deserialize(self)
     }
@@ -163,6 +224,12 @@ impl Value {
             ValueKind::Integer(value) => Ok(value != 0),
             ValueKind::Float(value) => Ok(value != 0.0),
 
+            ValueKind::Number(value) => Err(ConfigError::invalid_type(
+                self.origin,
+                Unexpected::Number(value),
+                "a boolean",
+            )),
+
             ValueKind::String(ref value) => {
                 match value.to_lowercase().as_ref() {
                     "1" | "true" | "on" | "yes" => Ok(true),
@@ -222,6 +289,17 @@ impl Value {
             ValueKind::Boolean(value) => Ok(if value { 1 } else { 0 }),
             ValueKind::Float(value) => Ok(value.round() as i64),
 
+            ValueKind::Number(value) => match value.parse() {
+                Ok(parsed) => Ok(parsed),
+
+                // Out of the range of i64
+                Err(_) => Err(ConfigError::invalid_type(
+                    self.origin,
+                    Unexpected::Number(value),
+                    "an integer in the range of i64",
+                )),
+            },
+
             // Unexpected type
             ValueKind::Nil => Err(ConfigError::invalid_type(
                 self.origin,
@@ -267,6 +345,17 @@ impl Value {
             ValueKind::Integer(value) => Ok(value as f64),
             ValueKind::Boolean(value) => Ok(if value { 1.0 } else { 0.0 }),
 
+            ValueKind::Number(value) => match value.parse::<f64>() {
+                Ok(parsed) if parsed.is_finite() => Ok(parsed),
+
+                // Out of the range of f64
+                _ => Err(ConfigError::invalid_type(
+                    self.origin,
+                    Unexpected::Number(value),
+                    "a floating point in the range of f64",
+                )),
+            },
+
             // Unexpected type
             ValueKind::Nil => Err(ConfigError::invalid_type(
                 self.origin,
@@ -295,6 +384,7 @@ impl Value {
             ValueKind::Boolean(value) => Ok(value.to_string()),
             ValueKind::Integer(value) => Ok(value.to_string()),
             ValueKind::Float(value) => Ok(value.to_string()),
+            ValueKind::Number(value) => Ok(value),
 
             // Cannot convert
             ValueKind::Nil => Err(ConfigError::invalid_type(
@@ -315,6 +405,22 @@ impl Value {
         }
     }
 
+    /// Returns `self` into a char, if it is a string of exactly one character.
+    pub(crate) fn into_char(self) -> Result<char> {
+        let origin = self.origin.clone();
+        let value = self.into_string()?;
+
+        let mut chars = value.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Ok(c),
+            _ => Err(ConfigError::invalid_type(
+                origin,
+                Unexpected::Str(value),
+                "a single character",
+            )),
+        }
+    }
+
     /// Returns `self` into an array, if possible
     // FIXME: Should this not be `try_into_*` ?
     pub fn into_array(self) -> Result<Vec<Value>> {
@@ -327,6 +433,11 @@ impl Value {
                 Unexpected::Float(value),
                 "an array",
             )),
+            ValueKind::Number(value) => Err(ConfigError::invalid_type(
+                self.origin,
+                Unexpected::Number(value),
+                "an array",
+            )),
             ValueKind::String(value) => Err(ConfigError::invalid_type(
                 self.origin,
                 Unexpected::Str(value),
@@ -367,6 +478,11 @@ impl Value {
                 Unexpected::Float(value),
                 "a map",
             )),
+            ValueKind::Number(value) => Err(ConfigError::invalid_type(
+                self.origin,
+                Unexpected::Number(value),
+                "a map",
+            )),
             ValueKind::String(value) => Err(ConfigError::invalid_type(
                 self.origin,
                 Unexpected::Str(value),
@@ -394,6 +510,52 @@ impl Value {
             )),
         }
     }
+
+    /// Returns `self` as the table backing the struct `name`, if possible.
+    ///
+    /// Unlike [`into_table`](Self::into_table), failures name the struct that was expected.
+    pub(crate) fn into_struct_table(self, name: &'static str) -> Result<Map<String, Value>> {
+        match self.kind {
+            ValueKind::Table(value) => Ok(value),
+
+            // Cannot convert
+            ValueKind::Float(value) => Err(ConfigError::invalid_type(
+                self.origin,
+                Unexpected::Float(value),
+                name,
+            )),
+            ValueKind::Number(value) => Err(ConfigError::invalid_type(
+                self.origin,
+                Unexpected::Number(value),
+                name,
+            )),
+            ValueKind::String(value) => Err(ConfigError::invalid_type(
+                self.origin,
+                Unexpected::Str(value),
+                name,
+            )),
+            ValueKind::Integer(value) => Err(ConfigError::invalid_type(
+                self.origin,
+                Unexpected::Integer(value),
+                name,
+            )),
+            ValueKind::Boolean(value) => Err(ConfigError::invalid_type(
+                self.origin,
+                Unexpected::Bool(value),
+                name,
+            )),
+            ValueKind::Nil => Err(ConfigError::invalid_type(
+                self.origin,
+                Unexpected::Unit,
+                name,
+            )),
+            ValueKind::Array(_) => Err(ConfigError::invalid_type(
+                self.origin,
+                Unexpected::Seq,
+                name,
+            )),
+        }
+    }
 }
 
 impl<'de> Deserialize<'de> for Value {
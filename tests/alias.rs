@@ -0,0 +1,70 @@
+extern crate config;
+extern crate serde;
+
+#[macro_use]
+extern crate serde_derive;
+
+use config::*;
+
+#[derive(Debug, Deserialize)]
+struct Server {
+    #[serde(alias = "hostname", alias = "server_host")]
+    host: String,
+    port: u16,
+}
+
+#[derive(Debug, Deserialize)]
+struct Settings {
+    #[serde(alias = "http")]
+    server: Server,
+}
+
+#[test]
+fn test_alias_primary_name() {
+    let c = Config::builder()
+        .set_default("server.host", "localhost")
+        .unwrap()
+        .set_default("server.port", 8080)
+        .unwrap()
+        .build()
+        .unwrap();
+
+    let settings: Settings = c.try_into().unwrap();
+    assert_eq!(settings.server.host, "localhost");
+    assert_eq!(settings.server.port, 8080);
+}
+
+#[test]
+fn test_alias_name() {
+    let c = Config::builder()
+        .set_default("http.hostname", "example.com")
+        .unwrap()
+        .set_default("http.port", 80)
+        .unwrap()
+        .build()
+        .unwrap();
+
+    let settings: Settings = c.try_into().unwrap();
+    assert_eq!(settings.server.host, "example.com");
+    assert_eq!(settings.server.port, 80);
+}
+
+#[test]
+fn test_alias_check_required() {
+    let builder = Config::builder()
+        .set_default("hostname", "example.com")
+        .unwrap();
+
+    assert_eq!(
+        builder.check_required::<Server>().unwrap_err().to_string(),
+        "no source provides the required fields: \"port\""
+    );
+
+    let builder = builder.set_default("port", 80).unwrap();
+    assert!(builder.check_required::<Server>().is_ok());
+
+    assert_eq!(
+        Environment::bind::<Server>(None).unwrap().required_keys(),
+        ["host", "port"]
+    );
+}
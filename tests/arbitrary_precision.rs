@@ -0,0 +1,67 @@
+#![cfg(feature = "arbitrary_precision")]
+
+extern crate config;
+
+use config::*;
+
+fn kind(c: &Config, key: &str) -> ValueKind {
+    c.cache.clone().into_table().unwrap()[key].kind.clone()
+}
+
+fn make() -> Config {
+    Config::builder()
+        .add_source(File::from_str(
+            r#"
+            {
+                "small": 42,
+                "unsigned": 18446744073709551615,
+                "huge": 123456789012345678901234567890,
+                "tiny": 0.5,
+                "overflow": 1e400
+            }
+            "#,
+            FileFormat::Json,
+        ))
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn test_huge_integer_preserved() {
+    let c = make();
+
+    assert_eq!(
+        kind(&c, "huge"),
+        ValueKind::Number("123456789012345678901234567890".into())
+    );
+    assert_eq!(
+        c.get_string("huge").unwrap(),
+        "123456789012345678901234567890".to_string()
+    );
+    assert_eq!(
+        c.get::<u128>("huge").unwrap(),
+        123456789012345678901234567890
+    );
+    assert_eq!(c.get::<u64>("unsigned").unwrap(), u64::MAX);
+
+    // Numbers that fit keep their usual kind
+    assert_eq!(kind(&c, "small"), ValueKind::Integer(42));
+    assert_eq!(kind(&c, "tiny"), ValueKind::Float(0.5));
+}
+
+#[test]
+fn test_overflow_error() {
+    let c = make();
+
+    let res = c.get::<i64>("huge");
+    assert_eq!(
+        res.unwrap_err().to_string(),
+        "invalid type: number `123456789012345678901234567890`, \
+         expected an integer in the range of i64 for key `huge`"
+            .to_string()
+    );
+
+    assert_eq!(kind(&c, "overflow"), ValueKind::Number("1e+400".into()));
+    assert!(c.get::<f64>("overflow").is_err());
+    assert!(c.get::<u64>("huge").is_err());
+}
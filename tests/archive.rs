@@ -0,0 +1,99 @@
+#![cfg(all(feature = "archive", feature = "toml", feature = "json"))]
+
+extern crate config;
+extern crate tar;
+extern crate zip;
+
+use std::io::{Cursor, Write};
+
+use config::*;
+
+fn make_zip(entries: &[(&str, &str)]) -> Vec<u8> {
+    let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+
+    for &(name, content) in entries {
+        writer
+            .start_file(name, zip::write::FileOptions::default())
+            .unwrap();
+        writer.write_all(content.as_bytes()).unwrap();
+    }
+
+    writer.finish().unwrap().into_inner()
+}
+
+fn make_tar(entries: &[(&str, &str)]) -> Vec<u8> {
+    let mut builder = tar::Builder::new(Vec::new());
+
+    for &(name, content) in entries {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(content.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder
+            .append_data(&mut header, name, content.as_bytes())
+            .unwrap();
+    }
+
+    builder.into_inner().unwrap()
+}
+
+#[test]
+fn test_zip_entries_merged_in_order() {
+    // Entries are stored out of order on purpose
+    let bytes = make_zip(&[
+        (
+            "conf/20-production.toml",
+            "[database]\nhost = \"db.example.com\"\n",
+        ),
+        (
+            "conf/10-base.toml",
+            "debug = true\n[database]\nhost = \"localhost\"\nport = 5432\n",
+        ),
+        ("README.md", "# Configuration"),
+    ]);
+
+    let config = Config::builder()
+        .add_source(Archive::from_bytes("bundle.zip", bytes, ArchiveFormat::Zip))
+        .build()
+        .unwrap();
+
+    assert_eq!(
+        config.get::<String>("database.host").unwrap(),
+        "db.example.com"
+    );
+    assert_eq!(config.get::<i64>("database.port").unwrap(), 5432);
+    assert!(config.get::<bool>("debug").unwrap());
+}
+
+#[test]
+fn test_tar_entries_pattern() {
+    let bytes = make_tar(&[
+        ("conf/app.toml", "name = \"app\"\n"),
+        ("conf/extra.json", "{\"extra\": 1}"),
+        ("other/ignored.toml", "ignored = true\n"),
+    ]);
+
+    let config = Config::builder()
+        .add_source(
+            source::Archive::from_bytes("bundle.tar", bytes, source::ArchiveFormat::Tar)
+                .entries("conf/*"),
+        )
+        .build()
+        .unwrap();
+
+    assert_eq!(config.get::<String>("name").unwrap(), "app");
+    assert_eq!(config.get::<i64>("extra").unwrap(), 1);
+    assert!(config.get::<bool>("ignored").is_err());
+}
+
+#[test]
+fn test_archive_error_names_entry() {
+    let bytes = make_zip(&[("conf/app.toml", "name = ")]);
+
+    let res = Config::builder()
+        .add_source(Archive::from_bytes("bundle.zip", bytes, ArchiveFormat::Zip))
+        .build();
+
+    let err = res.unwrap_err().to_string();
+    assert!(err.ends_with(" in bundle.zip:conf/app.toml"), "{}", err);
+}
@@ -0,0 +1,49 @@
+#![cfg(all(feature = "toml", feature = "json", feature = "yaml"))]
+
+extern crate config;
+
+use config::*;
+
+const FORMATS: &[FileFormat] = &[FileFormat::Toml, FileFormat::Json, FileFormat::Yaml];
+
+#[test]
+fn test_basename_merges_in_order() {
+    let c = Config::builder()
+        .add_basename("tests/basename/config", FORMATS, true)
+        .build()
+        .unwrap();
+
+    assert_eq!(c.get("name").ok(), Some("app".to_string()));
+    assert_eq!(c.get("port").ok(), Some(9090));
+    assert_eq!(c.get("database.host").ok(), Some("db.local".to_string()));
+    assert_eq!(c.get("database.user").ok(), Some("app".to_string()));
+}
+
+#[test]
+fn test_basename_single_format() {
+    let c = Config::builder()
+        .add_basename("tests/basename/solo", FORMATS, true)
+        .build()
+        .unwrap();
+
+    assert_eq!(c.get("name").ok(), Some("solo".to_string()));
+}
+
+#[test]
+fn test_basename_none_found() {
+    let res = Config::builder()
+        .add_basename("tests/basename/missing", FORMATS, true)
+        .build();
+
+    assert_eq!(
+        res.unwrap_err().to_string(),
+        "configuration file \"tests/basename/missing\" not found in any of the formats [Toml, Json, Yaml]"
+    );
+
+    let c = Config::builder()
+        .add_basename("tests/basename/missing", FORMATS, false)
+        .build()
+        .unwrap();
+
+    assert!(c.cache.into_table().unwrap().is_empty());
+}
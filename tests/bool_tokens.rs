@@ -0,0 +1,74 @@
+extern crate config;
+extern crate serde;
+
+#[macro_use]
+extern crate serde_derive;
+
+use config::*;
+
+#[derive(Debug, Deserialize)]
+struct Features {
+    cache: bool,
+    metrics: bool,
+    tracing: bool,
+}
+
+fn builder() -> ConfigBuilder<builder::DefaultState> {
+    Config::builder()
+        .set_default("features.cache", "Y")
+        .unwrap()
+        .set_default("features.metrics", "n")
+        .unwrap()
+        .set_default("features.tracing", true)
+        .unwrap()
+        .set_default("yes", "yes")
+        .unwrap()
+}
+
+#[test]
+fn test_bool_tokens() {
+    let c = builder().bool_tokens(&["y"], &["n"]).build().unwrap();
+
+    let features: Features = c.get("features").unwrap();
+    assert!(features.cache);
+    assert!(!features.metrics);
+    assert!(features.tracing);
+
+    assert!(c.get_bool("features.cache").unwrap());
+}
+
+#[test]
+fn test_bool_tokens_unknown() {
+    let c = builder().bool_tokens(&["y"], &["n"]).build().unwrap();
+
+    assert_eq!(
+        c.get::<bool>("yes").unwrap_err().to_string(),
+        "invalid type: string \"yes\", expected one of the configured boolean tokens for key `yes`"
+    );
+}
+
+#[test]
+fn test_default_bool_tokens() {
+    let c = builder().build().unwrap();
+
+    assert!(c.get::<bool>("yes").unwrap());
+    assert!(c.get::<Features>("features").is_err());
+}
+
+#[test]
+fn test_bool_from_integer() {
+    let c = Config::builder()
+        .set_default("features.cache", 1)
+        .unwrap()
+        .set_default("features.metrics", 0)
+        .unwrap()
+        .set_default("features.tracing", 1)
+        .unwrap()
+        .build()
+        .unwrap();
+
+    let features: Features = c.get("features").unwrap();
+    assert!(features.cache);
+    assert!(!features.metrics);
+    assert!(features.tracing);
+}
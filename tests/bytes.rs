@@ -0,0 +1,79 @@
+extern crate config;
+extern crate serde;
+
+use std::fmt;
+
+use config::*;
+use serde::de::{Deserialize, Deserializer, Visitor};
+
+/// Buffer deserialized with `deserialize_byte_buf`, like `serde_bytes::ByteBuf`
+#[derive(Debug, PartialEq)]
+struct Bytes(Vec<u8>);
+
+impl<'de> Deserialize<'de> for Bytes {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct BytesVisitor;
+
+        impl<'de> Visitor<'de> for BytesVisitor {
+            type Value = Bytes;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("bytes")
+            }
+
+            fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<Bytes, E> {
+                Ok(Bytes(v))
+            }
+
+            fn visit_bytes<E>(self, v: &[u8]) -> Result<Bytes, E> {
+                Ok(Bytes(v.to_vec()))
+            }
+        }
+
+        deserializer.deserialize_byte_buf(BytesVisitor)
+    }
+}
+
+fn builder() -> ConfigBuilder<builder::DefaultState> {
+    Config::builder()
+        .set_default("key", "aGVsbG8gd29ybGQ=")
+        .unwrap()
+        .set_default("nested.unpadded", "AAEC/w")
+        .unwrap()
+        .set_default("invalid", "not base64!")
+        .unwrap()
+}
+
+#[test]
+fn test_bytes_as_base64() {
+    let c = builder().bytes_as_base64(true).build().unwrap();
+
+    assert_eq!(
+        c.get::<Bytes>("key").unwrap(),
+        Bytes(b"hello world".to_vec())
+    );
+    assert_eq!(
+        c.get::<Bytes>("nested.unpadded").unwrap(),
+        Bytes(vec![0, 1, 2, 255])
+    );
+}
+
+#[test]
+fn test_bytes_as_base64_invalid() {
+    let c = builder().bytes_as_base64(true).build().unwrap();
+
+    assert_eq!(
+        c.get::<Bytes>("invalid").unwrap_err().to_string(),
+        "invalid type: string \"not base64!\", expected base64-encoded bytes for key `invalid`"
+    );
+}
+
+#[test]
+fn test_bytes_raw() {
+    let c = builder().build().unwrap();
+
+    assert_eq!(
+        c.get::<Bytes>("key").unwrap(),
+        Bytes(b"aGVsbG8gd29ybGQ=".to_vec())
+    );
+}
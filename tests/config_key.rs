@@ -0,0 +1,31 @@
+#![cfg(feature = "json")]
+
+extern crate config;
+
+use config::*;
+
+#[test]
+fn test_config_key() {
+    assert_eq!(config_key!(debug), "debug");
+    assert_eq!(config_key!(app.database.url), "app.database.url");
+    assert_eq!(config_key!(servers[1].ports[-1]), "servers[1].ports[-1]");
+}
+
+#[test]
+fn test_config_key_get() {
+    let config = Config::builder()
+        .add_source(File::from_str(
+            r#"{"app": {"database": {"url": "postgres"}}, "servers": [{"host": "a"}, {"host": "b"}]}"#,
+            FileFormat::Json,
+        ))
+        .build()
+        .unwrap();
+
+    const DATABASE_URL: &str = config_key!(app.database.url);
+
+    assert_eq!(config.get_string(DATABASE_URL).unwrap(), "postgres");
+    assert_eq!(
+        config.get_string(config_key!(servers[1].host)).unwrap(),
+        "b"
+    );
+}
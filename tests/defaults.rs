@@ -33,3 +33,111 @@ fn try_from_defaults() {
     let s: Settings = c.try_into().expect("Deserialization failed");
     assert_eq!(s.db_host, "default");
 }
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Server {
+    host: String,
+    port: u16,
+    tls: Tls,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Tls {
+    enabled: bool,
+    cert: String,
+}
+
+impl Default for Server {
+    fn default() -> Self {
+        Server {
+            host: "localhost".into(),
+            port: 8080,
+            tls: Tls {
+                enabled: false,
+                cert: "cert.pem".into(),
+            },
+        }
+    }
+}
+
+#[test]
+fn set_defaults_from_struct() {
+    let c = Config::builder()
+        .set_defaults_from(&Server::default())
+        .unwrap()
+        .set_override("port", 9090)
+        .unwrap()
+        .set_override("tls.enabled", true)
+        .unwrap()
+        .build()
+        .unwrap();
+
+    let s: Server = c.try_into().unwrap();
+    assert_eq!(s.host, "localhost");
+    assert_eq!(s.port, 9090);
+    assert!(s.tls.enabled);
+    assert_eq!(s.tls.cert, "cert.pem");
+}
+
+#[test]
+fn set_defaults_from_not_a_table() {
+    assert!(Config::builder().set_defaults_from(&42).is_err());
+}
+
+#[test]
+#[cfg(feature = "json")]
+fn test_deep_defaults() {
+    let builder = Config::builder()
+        .set_default("server.host", "localhost")
+        .unwrap()
+        .set_default("server.port", 80)
+        .unwrap()
+        .set_default("server.tls.enabled", false)
+        .unwrap()
+        .add_source(File::from_str(
+            r#"{"server": {"port": 8080, "tls": null}}"#,
+            FileFormat::Json,
+        ))
+        .deep_defaults(true);
+
+    let config = builder.build_cloned().unwrap();
+
+    assert_eq!(config.get::<String>("server.host").unwrap(), "localhost");
+    assert_eq!(config.get::<u16>("server.port").unwrap(), 8080);
+    // The nil table from the file is filled as well
+    assert!(!config.get::<bool>("server.tls.enabled").unwrap());
+
+    // Without deep defaults the sibling leaf survives too, but nil hides the default
+    let config = builder.deep_defaults(false).build().unwrap();
+
+    assert_eq!(config.get::<String>("server.host").unwrap(), "localhost");
+    assert_eq!(config.get::<u16>("server.port").unwrap(), 8080);
+    assert!(config.get::<bool>("server.tls.enabled").is_err());
+}
+
+#[test]
+#[cfg(feature = "json")]
+fn test_is_default() {
+    let config = Config::builder()
+        .set_default("server.host", "localhost")
+        .unwrap()
+        .set_default("server.port", 80)
+        .unwrap()
+        .set_default("debug", false)
+        .unwrap()
+        .add_source(File::from_str(
+            r#"{"server": {"port": 80}}"#,
+            FileFormat::Json,
+        ))
+        .set_override("debug", true)
+        .unwrap()
+        .build()
+        .unwrap();
+
+    assert!(config.is_default("server.host"));
+    // Set by the source, even though to the default value
+    assert!(!config.is_default("server.port"));
+    assert!(!config.is_default("server"));
+    assert!(!config.is_default("debug"));
+    assert!(!config.is_default("missing"));
+}
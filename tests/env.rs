@@ -449,3 +449,794 @@ fn test_parse_off_string() {
 
     env::remove_var("STRING_VAL_1");
 }
+
+#[test]
+fn test_chain_merges_prefixes() {
+    env::set_var("CHAIN_APP_NAME", "svc");
+    env::set_var("CHAIN_DB_URL", "postgres://localhost");
+
+    let environment =
+        Environment::with_prefix("CHAIN_APP").chain(Environment::with_prefix("CHAIN_DB"));
+
+    let m = environment.collect().unwrap();
+
+    assert_eq!(m.get("name").unwrap().clone().into_string().unwrap(), "svc");
+    assert_eq!(
+        m.get("url").unwrap().clone().into_string().unwrap(),
+        "postgres://localhost"
+    );
+
+    env::remove_var("CHAIN_APP_NAME");
+    env::remove_var("CHAIN_DB_URL");
+}
+
+#[test]
+fn test_chain_right_most_wins() {
+    env::set_var("CHAINL_PORT", "1");
+    env::set_var("CHAINR_PORT", "2");
+
+    let config = Config::builder()
+        .add_source(Environment::with_prefix("CHAINL").chain(Environment::with_prefix("CHAINR")))
+        .build()
+        .unwrap();
+
+    assert_eq!(config.get::<i32>("port").unwrap(), 2);
+
+    env::remove_var("CHAINL_PORT");
+    env::remove_var("CHAINR_PORT");
+}
+
+#[test]
+fn test_collision_last_in_sorted_order_wins() {
+    let mut vars = Map::new();
+    vars.insert("Path".to_string(), "lower".to_string());
+    vars.insert("PATH".to_string(), "upper".to_string());
+
+    for _ in 0..10 {
+        let m = Environment::new()
+            .source(Some(vars.clone()))
+            .collect()
+            .unwrap();
+
+        assert_eq!(m.len(), 1);
+        assert_eq!(m["path"].clone().into_string().unwrap(), "lower");
+    }
+}
+
+#[test]
+fn test_collision_detected() {
+    let mut vars = Map::new();
+    vars.insert("APP_PATH".to_string(), "upper".to_string());
+    vars.insert("app_path".to_string(), "lower".to_string());
+
+    let res = Environment::new()
+        .source(Some(vars))
+        .detect_collisions(true)
+        .collect();
+
+    assert_eq!(
+        res.unwrap_err().to_string(),
+        "environment variables \"APP_PATH\" and \"app_path\" both map to key \"app_path\""
+    );
+}
+
+#[test]
+fn test_source_replaces_process_environment() {
+    env::set_var("SOURCE_REPLACED", "process");
+
+    let mut vars = Map::new();
+    vars.insert("INJECTED".to_string(), "map".to_string());
+
+    let m = Environment::new().source(Some(vars)).collect().unwrap();
+
+    assert_eq!(m.len(), 1);
+    assert_eq!(m["injected"].clone().into_string().unwrap(), "map");
+
+    env::remove_var("SOURCE_REPLACED");
+}
+
+#[test]
+fn test_file_indirection() {
+    let path = env::temp_dir().join("config-rs-test-file-indirection");
+    std::fs::write(&path, "s3cr3t\n").unwrap();
+
+    let mut vars = Map::new();
+    vars.insert(
+        "APP_DB_PASSWORD_FILE".to_string(),
+        path.to_string_lossy().into_owned(),
+    );
+    vars.insert("APP_DB_USER".to_string(), "admin".to_string());
+
+    let environment = Environment::with_prefix("APP")
+        .separator("_")
+        .source(Some(vars))
+        .file_indirection(true);
+
+    let m = environment.clone().collect().unwrap();
+    assert_eq!(m["db.password"].clone().into_string().unwrap(), "s3cr3t");
+    assert_eq!(m["db.user"].clone().into_string().unwrap(), "admin");
+
+    let m = environment.trim_file_newline(false).collect().unwrap();
+    assert_eq!(m["db.password"].clone().into_string().unwrap(), "s3cr3t\n");
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_file_indirection_missing_file() {
+    let mut vars = Map::new();
+    vars.insert(
+        "APP_TOKEN_SECRET".to_string(),
+        "/nonexistent/config-rs/token".to_string(),
+    );
+
+    let res = Environment::with_prefix("APP")
+        .source(Some(vars))
+        .file_indirection(true)
+        .file_suffix("_SECRET")
+        .collect();
+
+    assert!(res
+        .unwrap_err()
+        .to_string()
+        .starts_with("could not read file \"/nonexistent/config-rs/token\" from environment variable \"APP_TOKEN_SECRET\""));
+}
+
+#[test]
+fn test_file_indirection_disabled() {
+    let mut vars = Map::new();
+    vars.insert("APP_KEY_FILE".to_string(), "/some/path".to_string());
+
+    let m = Environment::with_prefix("APP")
+        .source(Some(vars))
+        .collect()
+        .unwrap();
+
+    assert_eq!(m["key_file"].clone().into_string().unwrap(), "/some/path");
+}
+
+#[test]
+fn test_get_with_origin() {
+    let mut vars = Map::new();
+    vars.insert("APP_PORT".to_string(), "8080".to_string());
+
+    let config = Config::builder()
+        .add_source(
+            Environment::with_prefix("APP")
+                .source(Some(vars))
+                .try_parsing(true),
+        )
+        .set_override("host", "localhost")
+        .unwrap()
+        .build()
+        .unwrap();
+
+    let (port, origin) = config.get_with_origin::<u16>("port").unwrap();
+    assert_eq!(port, 8080);
+    assert_eq!(origin.as_deref(), Some("the environment"));
+
+    let (host, origin) = config.get_with_origin::<String>("host").unwrap();
+    assert_eq!(host, "localhost");
+    assert_eq!(origin, None);
+
+    assert!(matches!(
+        config.get_with_origin::<String>("missing"),
+        Err(ConfigError::NotFound(_))
+    ));
+}
+
+#[test]
+fn test_contains_filter() {
+    let mut vars = Map::new();
+    vars.insert("DB_SECRET_KEY".to_string(), "hunter2".to_string());
+    vars.insert("DB_HOST".to_string(), "localhost".to_string());
+
+    let m = Environment::new()
+        .source(Some(vars))
+        .contains_filter("_SECRET_")
+        .collect()
+        .unwrap();
+
+    assert_eq!(m.len(), 1);
+    assert_eq!(m["db_secret_key"].clone().into_string().unwrap(), "hunter2");
+}
+
+#[test]
+fn test_contains_filter_with_prefix() {
+    let mut vars = Map::new();
+    vars.insert("APP_DB_SECRET_KEY".to_string(), "hunter2".to_string());
+    vars.insert("APP_SECRET_KEY".to_string(), "ignored".to_string());
+    vars.insert("OTHER_DB_SECRET_KEY".to_string(), "ignored".to_string());
+
+    let m = Environment::with_prefix("APP")
+        .separator("_")
+        .source(Some(vars))
+        .contains_filter("_secret_")
+        .collect()
+        .unwrap();
+
+    assert_eq!(m.len(), 1);
+    assert_eq!(m["db.secret.key"].clone().into_string().unwrap(), "hunter2");
+}
+
+#[derive(Deserialize, Debug)]
+#[allow(dead_code)]
+struct BoundConfig {
+    host: String,
+    port: u16,
+    timeout: Option<u64>,
+    #[serde(default)]
+    debug: bool,
+}
+
+#[test]
+fn test_bind_required_keys() {
+    let environment = Environment::bind::<BoundConfig>(Some("APP")).unwrap();
+    assert_eq!(environment.required_keys(), ["host", "port"]);
+
+    let derived = Environment::bind::<BoundConfig>(None).unwrap();
+
+    let mut vars = Map::new();
+    vars.insert("BOUND_CONFIG_HOST".to_string(), "localhost".to_string());
+    vars.insert("BOUND_CONFIG_PORT".to_string(), "8080".to_string());
+
+    let config: BoundConfig = Config::builder()
+        .add_source(derived.source(Some(vars)))
+        .build()
+        .unwrap()
+        .try_into()
+        .unwrap();
+    assert_eq!(config.host, "localhost");
+    assert_eq!(config.port, 8080);
+}
+
+#[test]
+fn test_bind_missing_required() {
+    let mut vars = Map::new();
+    vars.insert("APP_HOST".to_string(), "localhost".to_string());
+    vars.insert("APP_TIMEOUT".to_string(), "5".to_string());
+
+    let res = Config::builder()
+        .add_source(
+            Environment::bind::<BoundConfig>(Some("APP"))
+                .unwrap()
+                .source(Some(vars)),
+        )
+        .build();
+
+    assert_eq!(
+        res.unwrap_err().to_string(),
+        "missing environment variables for required fields: \"APP_PORT\""
+    );
+}
+
+#[test]
+fn test_bind_not_a_struct() {
+    assert!(Environment::bind::<Vec<String>>(Some("APP")).is_err());
+}
+
+#[test]
+fn test_without_lowercasing() {
+    let mut vars = Map::new();
+    vars.insert("app_MY_Value".to_string(), "mixed".to_string());
+    vars.insert("APP_Nested_Key".to_string(), "nested".to_string());
+
+    let m = Environment::with_prefix("APP")
+        .separator("_")
+        .source(Some(vars.clone()))
+        .without_lowercasing()
+        .collect()
+        .unwrap();
+
+    assert_eq!(m["MY.Value"].clone().into_string().unwrap(), "mixed");
+    assert_eq!(m["Nested.Key"].clone().into_string().unwrap(), "nested");
+
+    let m = Environment::with_prefix("APP")
+        .source(Some(vars))
+        .without_lowercasing()
+        .collect()
+        .unwrap();
+
+    assert!(m.contains_key("MY_Value"));
+    assert!(!m.contains_key("my_value"));
+}
+
+#[test]
+fn test_without_lowercasing_mixed_case_prefix() {
+    let mut vars = Map::new();
+    vars.insert("App_Debug".to_string(), "true".to_string());
+
+    let m = Environment::with_prefix("APP")
+        .source(Some(vars))
+        .without_lowercasing()
+        .collect()
+        .unwrap();
+
+    assert_eq!(m.keys().collect::<Vec<_>>(), ["Debug"]);
+}
+
+#[test]
+fn test_bare_prefix_skipped() {
+    let mut vars = Map::new();
+    vars.insert("APP".to_string(), "bare".to_string());
+    vars.insert("APP_".to_string(), "empty".to_string());
+    vars.insert("APP_DEBUG".to_string(), "true".to_string());
+
+    let m = Environment::with_prefix("APP")
+        .separator("_")
+        .source(Some(vars))
+        .collect()
+        .unwrap();
+
+    assert_eq!(m.len(), 1);
+    assert!(m.contains_key("debug"));
+}
+
+#[test]
+fn test_prefix_as_key() {
+    let mut vars = Map::new();
+    vars.insert("APP".to_string(), "production".to_string());
+    vars.insert("APP_DEBUG".to_string(), "true".to_string());
+
+    let m = Environment::with_prefix("APP")
+        .separator("_")
+        .source(Some(vars))
+        .prefix_as_key(Some("profile.name"))
+        .collect()
+        .unwrap();
+
+    assert_eq!(m.len(), 2);
+    assert_eq!(
+        m["profile.name"].clone().into_string().unwrap(),
+        "production"
+    );
+}
+
+#[test]
+fn test_drop_leading_segments() {
+    let mut vars = Map::new();
+    vars.insert("MYAPP_DATABASE_URL".to_string(), "postgres".to_string());
+    vars.insert("OTHER_DEBUG".to_string(), "true".to_string());
+    vars.insert("SHORT".to_string(), "skipped".to_string());
+
+    let m = Environment::new()
+        .separator("_")
+        .source(Some(vars.clone()))
+        .drop_leading_segments(1)
+        .collect()
+        .unwrap();
+
+    assert_eq!(m.len(), 2);
+    assert_eq!(m["database.url"].clone().into_string().unwrap(), "postgres");
+    assert_eq!(m["debug"].clone().into_string().unwrap(), "true");
+
+    let m = Environment::new()
+        .separator("_")
+        .source(Some(vars))
+        .drop_leading_segments(3)
+        .collect()
+        .unwrap();
+
+    assert!(m.is_empty());
+}
+
+#[test]
+fn test_drop_leading_segments_with_prefix() {
+    let mut vars = Map::new();
+    vars.insert(
+        "CORP_MYAPP_DATABASE_URL".to_string(),
+        "postgres".to_string(),
+    );
+    vars.insert("MYAPP_DEBUG".to_string(), "ignored".to_string());
+
+    let m = Environment::with_prefix("CORP")
+        .separator("_")
+        .source(Some(vars))
+        .drop_leading_segments(1)
+        .collect()
+        .unwrap();
+
+    assert_eq!(m.len(), 1);
+    assert_eq!(m["database.url"].clone().into_string().unwrap(), "postgres");
+}
+
+#[test]
+fn test_keep_prefix() {
+    let mut vars = Map::new();
+    vars.insert("APP_DEBUG".to_string(), "true".to_string());
+    vars.insert("APP_DATABASE_URL".to_string(), "postgres".to_string());
+    vars.insert("OTHER_DEBUG".to_string(), "false".to_string());
+
+    let m = Environment::with_prefix("APP")
+        .source(Some(vars.clone()))
+        .keep_prefix(true)
+        .collect()
+        .unwrap();
+
+    assert_eq!(m.len(), 2);
+    assert_eq!(m["app_debug"].clone().into_string().unwrap(), "true");
+    assert_eq!(
+        m["app_database_url"].clone().into_string().unwrap(),
+        "postgres"
+    );
+
+    let m = Environment::with_prefix("APP")
+        .separator("_")
+        .source(Some(vars))
+        .keep_prefix(true)
+        .collect()
+        .unwrap();
+
+    assert_eq!(m.len(), 2);
+    assert_eq!(m["app.debug"].clone().into_string().unwrap(), "true");
+    assert_eq!(
+        m["app.database.url"].clone().into_string().unwrap(),
+        "postgres"
+    );
+}
+
+#[test]
+fn test_float_policy() {
+    let mut vars = Map::new();
+    vars.insert("INF".to_string(), "inf".to_string());
+    vars.insert("NAN".to_string(), "nan".to_string());
+    vars.insert("RATIO".to_string(), "0.5".to_string());
+
+    let m = Environment::new()
+        .try_parsing(true)
+        .source(Some(vars.clone()))
+        .collect()
+        .unwrap();
+
+    assert!(matches!(m["inf"].kind, ValueKind::Float(f) if f.is_infinite()));
+    assert!(matches!(m["nan"].kind, ValueKind::Float(f) if f.is_nan()));
+    assert_eq!(m["ratio"].kind, ValueKind::Float(0.5));
+
+    let m = Environment::new()
+        .try_parsing(true)
+        .float_policy(FloatPolicy::Reject)
+        .source(Some(vars))
+        .collect()
+        .unwrap();
+
+    assert_eq!(m["inf"].kind, ValueKind::String("inf".into()));
+    assert_eq!(m["nan"].kind, ValueKind::String("nan".into()));
+    assert_eq!(m["ratio"].kind, ValueKind::Float(0.5));
+}
+
+#[test]
+fn test_builder_float_policy() {
+    let builder = Config::builder()
+        .set_default("limit", f64::INFINITY)
+        .unwrap()
+        .set_default("ratio", 0.5)
+        .unwrap();
+
+    let config = builder.build_cloned().unwrap();
+    assert_eq!(config.get::<f64>("limit").unwrap(), f64::INFINITY);
+
+    let config = builder.float_policy(FloatPolicy::Reject).build().unwrap();
+    assert_eq!(config.get::<String>("limit").unwrap(), "inf");
+    assert!(config.get::<f64>("limit").is_err());
+    assert_eq!(config.get::<f64>("ratio").unwrap(), 0.5);
+}
+
+#[test]
+fn test_strict_int_parse() {
+    let mut vars = Map::new();
+    vars.insert("ZIP".to_string(), "007".to_string());
+    vars.insert("OFFSET".to_string(), "-012".to_string());
+    vars.insert("ZERO".to_string(), "0".to_string());
+    vars.insert("RATIO".to_string(), "0.5".to_string());
+
+    let m = Environment::new()
+        .try_parsing(true)
+        .source(Some(vars.clone()))
+        .collect()
+        .unwrap();
+
+    assert_eq!(m["zip"].kind, ValueKind::Integer(7));
+    assert_eq!(m["offset"].kind, ValueKind::Integer(-12));
+    assert_eq!(m["zero"].kind, ValueKind::Integer(0));
+
+    let m = Environment::new()
+        .try_parsing(true)
+        .strict_int_parse(true)
+        .source(Some(vars))
+        .collect()
+        .unwrap();
+
+    assert_eq!(m["zip"].kind, ValueKind::String("007".into()));
+    assert_eq!(m["offset"].kind, ValueKind::String("-012".into()));
+    assert_eq!(m["zero"].kind, ValueKind::Integer(0));
+    assert_eq!(m["ratio"].kind, ValueKind::Float(0.5));
+}
+
+#[test]
+fn test_numeric_separators() {
+    let mut vars = Map::new();
+    vars.insert("LIMIT".to_string(), "1_000".to_string());
+    vars.insert("BUDGET".to_string(), "-1,000,000".to_string());
+    vars.insert("RATIO".to_string(), "1_000.5".to_string());
+    vars.insert("SCALE".to_string(), "1e3".to_string());
+    vars.insert("NAMES".to_string(), "a,b".to_string());
+
+    let m = Environment::new()
+        .try_parsing(true)
+        .source(Some(vars.clone()))
+        .collect()
+        .unwrap();
+
+    assert_eq!(m["limit"].kind, ValueKind::String("1_000".into()));
+    assert_eq!(m["budget"].kind, ValueKind::String("-1,000,000".into()));
+    assert_eq!(m["scale"].kind, ValueKind::Float(1000.0));
+
+    let m = Environment::new()
+        .try_parsing(true)
+        .numeric_separators(&['_', ','])
+        .source(Some(vars))
+        .collect()
+        .unwrap();
+
+    assert_eq!(m["limit"].kind, ValueKind::Integer(1000));
+    assert_eq!(m["budget"].kind, ValueKind::Integer(-1_000_000));
+    assert_eq!(m["ratio"].kind, ValueKind::Float(1000.5));
+    assert_eq!(m["names"].kind, ValueKind::String("a,b".into()));
+}
+
+#[test]
+fn test_collect_raw() {
+    let mut vars = Map::new();
+    vars.insert("APP_PORT".to_string(), "8080".to_string());
+    vars.insert("APP_DEBUG".to_string(), "TRUE".to_string());
+    vars.insert("APP_RATIO".to_string(), "0.50".to_string());
+
+    let environment = Environment::with_prefix("APP")
+        .try_parsing(true)
+        .source(Some(vars));
+
+    let raw = environment.collect_raw().unwrap();
+    assert_eq!(raw.len(), 3);
+    assert_eq!(raw["port"], "8080");
+    assert_eq!(raw["debug"], "TRUE");
+    assert_eq!(raw["ratio"], "0.50");
+
+    let m = environment.collect().unwrap();
+    assert_eq!(m["port"].kind, ValueKind::Integer(8080));
+    assert_eq!(m["debug"].kind, ValueKind::Boolean(true));
+    assert_eq!(m["ratio"].kind, ValueKind::Float(0.5));
+}
+
+#[test]
+#[cfg(feature = "toml")]
+fn test_apply_env() {
+    env::set_var("LATE_PLACE_NAME", "Colosseum");
+
+    let mut config = Config::builder()
+        .add_source(File::new("tests/Settings", FileFormat::Toml))
+        .set_override("place.rating", 5)
+        .unwrap()
+        .build()
+        .unwrap();
+
+    config
+        .apply_env(Environment::with_prefix("LATE").separator("_"))
+        .unwrap();
+
+    assert_eq!(config.get("place.name").ok(), Some("Colosseum".to_string()));
+    assert_eq!(config.get("place.number").ok(), Some(1));
+    assert_eq!(config.get("place.rating").ok(), Some(5));
+
+    env::remove_var("LATE_PLACE_NAME");
+}
+
+#[test]
+fn test_allowlist() {
+    let mut vars = Map::new();
+    vars.insert("APP__DATABASE__URL".to_string(), "postgres://".to_string());
+    vars.insert("APP__DATABASE__POOL".to_string(), "4".to_string());
+    vars.insert("APP__DEBUG".to_string(), "true".to_string());
+
+    let environment = Environment::with_prefix("APP")
+        .separator("__")
+        .source(Some(vars))
+        .allowlist(&["database.url", "debug"]);
+
+    let m = environment.collect().unwrap();
+    assert_eq!(m.len(), 2);
+    assert_eq!(
+        m["database.url"].clone().into_string().unwrap(),
+        "postgres://"
+    );
+    assert_eq!(m["debug"].clone().into_string().unwrap(), "true");
+    assert!(!m.contains_key("database.pool"));
+}
+
+#[test]
+fn test_allowlist_require_keys() {
+    let mut vars = Map::new();
+    vars.insert("APP__DATABASE__URL".to_string(), "postgres://".to_string());
+    vars.insert("APP__DATABASE__POOL".to_string(), "4".to_string());
+
+    let environment = Environment::with_prefix("APP")
+        .separator("__")
+        .source(Some(vars))
+        .require_keys(&["database.url", "database.pool"]);
+
+    assert!(environment.clone().collect().is_ok());
+
+    // A present variable that is not allowed does not satisfy a requirement
+    let res = environment.allowlist(&["database.url"]).collect();
+    assert_eq!(
+        res.unwrap_err().to_string(),
+        "missing environment variables for required fields: \"APP__DATABASE__POOL\""
+    );
+}
+
+#[test]
+fn test_nest_under() {
+    let mut vars = Map::new();
+    vars.insert("APP__PORT".to_string(), "8080".to_string());
+    vars.insert("APP__DATABASE__URL".to_string(), "postgres://".to_string());
+
+    let environment = Environment::with_prefix("APP")
+        .separator("__")
+        .nest_under("env");
+
+    let m = environment
+        .clone()
+        .source(Some(vars.clone()))
+        .collect()
+        .unwrap();
+    assert_eq!(m.len(), 2);
+    assert!(m.contains_key("env.port"));
+    assert!(m.contains_key("env.database.url"));
+
+    let config = Config::builder()
+        .set_default("port", 80)
+        .unwrap()
+        .add_source(environment.try_parsing(true).source(Some(vars)))
+        .build()
+        .unwrap();
+
+    assert_eq!(config.get::<i64>("port").unwrap(), 80);
+    assert_eq!(config.get::<i64>("env.port").unwrap(), 8080);
+    assert_eq!(
+        config.get::<String>("env.database.url").unwrap(),
+        "postgres://"
+    );
+}
+
+#[test]
+fn test_iter() {
+    let mut vars = Map::new();
+    vars.insert("APP__PORT".to_string(), "8080".to_string());
+    vars.insert("APP__DEBUG".to_string(), "true".to_string());
+    vars.insert("APP__DATABASE__URL".to_string(), "postgres://".to_string());
+    vars.insert("OTHER".to_string(), "skipped".to_string());
+
+    let environment = Environment::with_prefix("APP")
+        .separator("__")
+        .try_parsing(true)
+        .source(Some(vars));
+
+    let pairs: Vec<(String, Value)> = environment.iter().collect::<Result<_, _>>().unwrap();
+    assert_eq!(pairs.len(), 3);
+
+    let collected = environment.collect().unwrap();
+    for (key, value) in pairs {
+        assert_eq!(value.kind, collected[&key].kind);
+        assert_eq!(value.origin(), collected[&key].origin());
+    }
+
+    let port = environment
+        .iter()
+        .map(Result::unwrap)
+        .find(|(key, _)| key == "port")
+        .unwrap()
+        .1;
+    assert_eq!(port.kind, ValueKind::Integer(8080));
+}
+
+#[test]
+fn test_list_keys_from_schema() {
+    let mut vars = Map::new();
+    vars.insert("APP__HOSTS".to_string(), "a.example,b.example".to_string());
+    vars.insert("APP__DATABASE__PORTS".to_string(), "5432,5433".to_string());
+    vars.insert("APP__NAME".to_string(), "a,b".to_string());
+
+    let config = Config::builder()
+        .add_source(
+            Environment::with_prefix("APP")
+                .separator("__")
+                .list_separator(",")
+                .list_keys_from_schema(&["hosts", "database.ports"])
+                .try_parsing(true)
+                .source(Some(vars)),
+        )
+        .build()
+        .unwrap();
+
+    assert_eq!(
+        config.get::<Vec<String>>("hosts").unwrap(),
+        ["a.example", "b.example"]
+    );
+    assert_eq!(
+        config.get::<Vec<u16>>("database.ports").unwrap(),
+        [5432, 5433]
+    );
+    assert_eq!(config.get::<String>("name").unwrap(), "a,b");
+}
+
+#[test]
+#[cfg(feature = "toml")]
+fn test_inherit_token() {
+    let mut vars = Map::new();
+    vars.insert("APP_HOST".to_string(), "__inherit__".to_string());
+    vars.insert("APP_PORT".to_string(), "9090".to_string());
+
+    let config = Config::builder()
+        .add_source(File::from_str(
+            "host = \"localhost\"\nport = 8080",
+            FileFormat::Toml,
+        ))
+        .add_source(
+            Environment::with_prefix("APP")
+                .inherit_token("__inherit__")
+                .try_parsing(true)
+                .source(Some(vars)),
+        )
+        .build()
+        .unwrap();
+
+    assert_eq!(config.get::<String>("host").unwrap(), "localhost");
+    assert_eq!(config.get::<i64>("port").unwrap(), 9090);
+}
+
+#[test]
+#[cfg(feature = "json")]
+fn test_json_array_key() {
+    let mut vars = Map::new();
+    vars.insert("APP_PORTS".to_string(), "[8080, 8081]".to_string());
+    vars.insert(
+        "APP_HOSTS".to_string(),
+        r#"["a.example", "b.example"]"#.to_string(),
+    );
+    vars.insert("APP_NAME".to_string(), "[app]".to_string());
+
+    let environment = Environment::with_prefix("APP")
+        .with_json_array_key("ports")
+        .with_json_array_key("hosts");
+
+    let config = Config::builder()
+        .add_source(environment.clone().source(Some(vars.clone())))
+        .build()
+        .unwrap();
+
+    assert_eq!(config.get::<Vec<u16>>("ports").unwrap(), [8080, 8081]);
+    assert_eq!(
+        config.get::<Vec<String>>("hosts").unwrap(),
+        ["a.example", "b.example"]
+    );
+    assert_eq!(config.get::<String>("name").unwrap(), "[app]");
+
+    vars.insert("APP_PORTS".to_string(), "[8080,".to_string());
+    let malformed = environment.clone().source(Some(vars.clone()));
+    assert!(malformed
+        .collect()
+        .unwrap_err()
+        .to_string()
+        .starts_with("value of key \"ports\" in the environment is not a JSON array: "));
+    let errors = malformed.iter().filter(|item| item.is_err()).count();
+    assert_eq!(errors, 1);
+
+    vars.insert("APP_PORTS".to_string(), "8080".to_string());
+    assert_eq!(
+        environment
+            .source(Some(vars))
+            .collect()
+            .unwrap_err()
+            .to_string(),
+        "value of key \"ports\" in the environment is not a JSON array: found 8080"
+    );
+}
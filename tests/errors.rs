@@ -154,3 +154,110 @@ fn test_error_root_not_table() {
         },
     }
 }
+
+#[test]
+fn test_error_struct_type() {
+    #[derive(Debug, Deserialize)]
+    #[allow(dead_code)]
+    struct Place {
+        name: String,
+    }
+
+    let c = make();
+    let res = c.get::<Place>("debug");
+
+    let path: PathBuf = ["tests", "Settings.toml"].iter().collect();
+
+    assert_eq!(
+        res.unwrap_err().to_string(),
+        format!(
+            "invalid type: boolean `true`, expected Place for key `debug` in {}",
+            path.display()
+        )
+    );
+}
+
+#[test]
+fn test_error_nested_struct_type() {
+    #[derive(Debug, Deserialize)]
+    #[allow(dead_code)]
+    struct Pattern {
+        name: String,
+    }
+
+    #[derive(Debug, Deserialize)]
+    #[allow(dead_code)]
+    struct Diodes {
+        green: Pattern,
+    }
+
+    let c = make();
+    let e = c.get::<Diodes>("diodes").unwrap_err();
+
+    if let ConfigError::Type {
+        ref key, expected, ..
+    } = e
+    {
+        assert_eq!(key.as_deref(), Some("diodes.green"));
+        assert_eq!(expected, "Pattern");
+    } else {
+        panic!("Wrong error {:?}", e);
+    }
+}
+
+#[test]
+fn test_error_kind() {
+    let c = make();
+
+    assert_eq!(
+        c.get::<bool>("not_found").unwrap_err().kind(),
+        ConfigErrorKind::NotFound
+    );
+    assert_eq!(
+        c.get::<bool>("boolean_s_parse").unwrap_err().kind(),
+        ConfigErrorKind::Type
+    );
+    assert_eq!(
+        c.get::<bool>("a..b").unwrap_err().kind(),
+        ConfigErrorKind::PathParse
+    );
+
+    let res = Config::builder()
+        .add_source(File::new("tests/Settings-invalid", FileFormat::Toml))
+        .build();
+    assert_eq!(res.unwrap_err().kind(), ConfigErrorKind::FileParse);
+}
+
+#[test]
+fn test_build_collecting_errors() {
+    let errors = Config::builder()
+        .add_source(File::new("tests/Settings-invalid", FileFormat::Toml))
+        .add_source(File::new("tests/Settings", FileFormat::Toml))
+        .add_source(File::from_str("debug = ", FileFormat::Toml).origin("inline.toml"))
+        .build_collecting_errors()
+        .unwrap_err();
+
+    assert_eq!(errors.len(), 2);
+    assert!(errors
+        .iter()
+        .all(|error| matches!(error, ConfigError::FileParse { .. })));
+    assert!(errors[0].to_string().contains("Settings-invalid.toml"));
+    assert!(errors[1].to_string().contains("inline.toml"));
+}
+
+#[test]
+fn test_build_collecting_errors_single() {
+    let errors = Config::builder()
+        .add_source(File::new("tests/Settings", FileFormat::Toml))
+        .add_source(File::new("tests/Settings-invalid", FileFormat::Toml))
+        .build_collecting_errors()
+        .unwrap_err();
+
+    assert_eq!(errors.len(), 1);
+
+    let c = Config::builder()
+        .add_source(File::new("tests/Settings", FileFormat::Toml))
+        .build_collecting_errors()
+        .unwrap();
+    assert_eq!(c.get("debug").ok(), Some(true));
+}
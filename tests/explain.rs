@@ -0,0 +1,56 @@
+#![cfg(feature = "toml")]
+
+extern crate config;
+
+use config::*;
+
+fn builder() -> ConfigBuilder<builder::DefaultState> {
+    Config::builder()
+        .set_default("server.port", 80)
+        .unwrap()
+        .add_source(
+            File::from_str(
+                "[server]\nhost = \"localhost\"\nport = 8080",
+                FileFormat::Toml,
+            )
+            .origin("base.toml"),
+        )
+        .add_source(File::from_str("[server]\nport = 9090", FileFormat::Toml).origin("local.toml"))
+}
+
+#[test]
+fn test_explain() {
+    let explanation = builder().explain().unwrap();
+
+    let keys: Vec<&str> = explanation
+        .keys()
+        .iter()
+        .map(|explained| explained.key.as_str())
+        .collect();
+    assert_eq!(keys, ["server.host", "server.port"]);
+
+    let host = explanation.get("server.host").unwrap();
+    assert_eq!(host.value.origin(), Some("base.toml"));
+    assert!(host.shadowed.is_empty());
+
+    let port = explanation.get("server.port").unwrap();
+    assert_eq!(port.value.clone().into_int().unwrap(), 9090);
+    assert_eq!(port.value.origin(), Some("local.toml"));
+    let shadowed: Vec<(i64, Option<&str>)> = port
+        .shadowed
+        .iter()
+        .map(|value| (value.clone().into_int().unwrap(), value.origin()))
+        .collect();
+    assert_eq!(shadowed, [(80, None), (8080, Some("base.toml"))]);
+}
+
+#[test]
+fn test_explain_display() {
+    assert_eq!(
+        builder().explain().unwrap().to_string(),
+        "server.host = localhost (base.toml)\n\
+         server.port = 9090 (local.toml)\n    \
+         shadows 8080 (base.toml)\n    \
+         shadows 80\n"
+    );
+}
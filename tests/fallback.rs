@@ -0,0 +1,69 @@
+#![cfg(feature = "toml")]
+
+extern crate config;
+
+use config::*;
+
+fn bundled() -> File<FileSourceString> {
+    File::from_str("origin = \"bundled\"\nbundled = true", FileFormat::Toml)
+}
+
+#[test]
+fn test_fallback_primary_empty() {
+    let source = Fallback::new(
+        File::optional("tests/NoSettings", FileFormat::Toml),
+        bundled(),
+    );
+    assert_eq!(source.used_secondary(), None);
+
+    let c = Config::builder()
+        .add_source(source.clone())
+        .build()
+        .unwrap();
+
+    assert_eq!(c.get("origin").ok(), Some("bundled".to_string()));
+    assert_eq!(source.used_secondary(), Some(true));
+    assert!(source.primary_error().is_none());
+}
+
+#[test]
+fn test_fallback_primary_used() {
+    let source = Fallback::new(
+        File::from_str("origin = \"remote\"", FileFormat::Toml),
+        bundled(),
+    );
+
+    let c = Config::builder()
+        .add_source(source.clone())
+        .build()
+        .unwrap();
+
+    assert_eq!(c.get("origin").ok(), Some("remote".to_string()));
+    assert!(!c.contains_key("bundled"));
+    assert_eq!(source.used_secondary(), Some(false));
+}
+
+#[test]
+fn test_fallback_primary_error() {
+    let source = Fallback::new(File::from_str("origin = ", FileFormat::Toml), bundled());
+
+    let c = Config::builder()
+        .add_source(source.clone())
+        .build()
+        .unwrap();
+
+    assert_eq!(c.get("origin").ok(), Some("bundled".to_string()));
+    assert_eq!(source.used_secondary(), Some(true));
+    assert_eq!(
+        source.primary_error().unwrap().kind(),
+        ConfigErrorKind::FileParse
+    );
+
+    let res = Config::builder()
+        .add_source(Fallback::new(
+            File::from_str("origin = ", FileFormat::Toml),
+            File::new("tests/NoSettings", FileFormat::Toml),
+        ))
+        .build();
+    assert!(res.is_err());
+}
@@ -26,6 +26,25 @@ fn test_file_required_not_found() {
     );
 }
 
+#[test]
+fn test_file_optional_not_found() {
+    let c = Config::builder()
+        .add_source(File::optional("tests/NoSettings", FileFormat::Yaml))
+        .build()
+        .unwrap();
+
+    assert!(c.cache.into_table().unwrap().is_empty());
+}
+
+#[test]
+fn test_file_optional_invalid() {
+    let res = Config::builder()
+        .add_source(File::optional("tests/Settings-invalid", FileFormat::Yaml))
+        .build();
+
+    assert!(matches!(res, Err(ConfigError::FileParse { .. })));
+}
+
 #[test]
 fn test_file_auto() {
     let c = Config::builder()
@@ -60,3 +79,72 @@ fn test_file_ext() {
     assert_eq!(c.get("debug").ok(), Some(true));
     assert_eq!(c.get("production").ok(), Some(false));
 }
+
+#[test]
+fn test_file_expand_path() {
+    std::env::set_var("EXPAND_PATH_CONFIG_DIR", "tests");
+
+    let c = Config::builder()
+        .add_source(
+            File::new("${EXPAND_PATH_CONFIG_DIR}/Settings", FileFormat::Yaml).expand_path(true),
+        )
+        .build()
+        .unwrap();
+
+    assert_eq!(c.get("debug").ok(), Some(true));
+}
+
+#[test]
+fn test_file_expand_path_missing_var() {
+    let res = Config::builder()
+        .add_source(File::new("${EXPAND_PATH_UNSET}/Settings", FileFormat::Yaml).expand_path(true))
+        .build();
+
+    assert_eq!(
+        res.unwrap_err().to_string(),
+        "environment variable \"EXPAND_PATH_UNSET\" in path \"${EXPAND_PATH_UNSET}/Settings\" is not set"
+    );
+
+    // Without expansion the path is taken literally
+    let res = Config::builder()
+        .add_source(File::new("${EXPAND_PATH_UNSET}/Settings", FileFormat::Yaml))
+        .build();
+
+    assert_eq!(
+        res.unwrap_err().to_string(),
+        "configuration file \"${EXPAND_PATH_UNSET}/Settings\" not found"
+    );
+}
+
+#[test]
+fn test_file_relative_to() {
+    let c = Config::builder()
+        .add_source(File::relative_to("tests", "Settings", FileFormat::Yaml))
+        .build()
+        .unwrap();
+
+    assert_eq!(c.get("debug").ok(), Some(true));
+}
+
+#[test]
+fn test_file_relative_to_not_found() {
+    let res = Config::builder()
+        .add_source(File::relative_to("tests", "NoSettings", FileFormat::Yaml))
+        .build();
+
+    let path = std::env::current_dir()
+        .unwrap()
+        .join("tests")
+        .join("NoSettings");
+    assert_eq!(
+        res.unwrap_err().to_string(),
+        format!("configuration file \"{}\" not found", path.display())
+    );
+
+    let c = Config::builder()
+        .add_source(File::relative_to("tests", "NoSettings", FileFormat::Yaml).required(false))
+        .build()
+        .unwrap();
+
+    assert!(c.cache.into_table().unwrap().is_empty());
+}
@@ -0,0 +1,34 @@
+#![cfg(all(feature = "toml", feature = "json"))]
+
+extern crate config;
+
+use config::*;
+
+#[test]
+fn test_formats_first_that_parses() {
+    let c = Config::builder()
+        .add_source(File::from_with_formats(
+            "tests/detect/config",
+            &[FileFormat::Toml, FileFormat::Json],
+        ))
+        .build()
+        .unwrap();
+
+    assert_eq!(c.get("name").ok(), Some("app".to_string()));
+    assert_eq!(c.get("port").ok(), Some(8080));
+}
+
+#[test]
+fn test_formats_none_parses() {
+    let res = Config::builder()
+        .add_source(File::from_with_formats(
+            "tests/detect/garbage",
+            &[FileFormat::Toml, FileFormat::Json],
+        ))
+        .build();
+
+    let message = res.unwrap_err().to_string();
+    assert!(message.starts_with("no format could parse the file (Toml: "));
+    assert!(message.contains("; Json: "));
+    assert!(message.ends_with("tests/detect/garbage"));
+}
@@ -116,3 +116,69 @@ fn test_json_vec() {
     assert_eq!(vi.next().unwrap().into_string().unwrap(), "example_dir2");
     assert!(vi.next().is_none());
 }
+
+#[test]
+fn test_from_reader() {
+    let input = std::io::Cursor::new(r#"{ "debug": true, "server": { "port": 8080 } }"#);
+    let source = File::from_reader(input, FileFormat::Json, "embedded.json");
+
+    let c = Config::builder()
+        .add_source(source.clone())
+        .build()
+        .unwrap();
+
+    assert!(c.get::<bool>("debug").unwrap());
+    assert_eq!(c.get::<u16>("server.port").unwrap(), 8080);
+    assert_eq!(
+        c.get_with_origin::<bool>("debug").unwrap().1.as_deref(),
+        Some("embedded.json")
+    );
+
+    // The reader is consumed, later collections reuse its content
+    let m = source.collect().unwrap();
+    assert!(m.contains_key("server"));
+}
+
+#[test]
+fn test_from_reader_empty() {
+    let res = Config::builder()
+        .add_source(File::from_reader(
+            std::io::empty(),
+            FileFormat::Json,
+            "embedded.json",
+        ))
+        .build();
+    assert_eq!(res.unwrap_err().to_string(), "embedded.json is empty");
+
+    let c = Config::builder()
+        .add_source(
+            File::from_reader(std::io::empty(), FileFormat::Json, "embedded.json").required(false),
+        )
+        .build()
+        .unwrap();
+    assert!(c.cache.into_table().unwrap().is_empty());
+}
+
+#[test]
+fn test_from_reader_origin() {
+    let input = std::io::Cursor::new(r#"{ "debug": true, }"#);
+    let res = Config::builder()
+        .add_source(File::from_reader(input, FileFormat::Json, "embedded.json"))
+        .build();
+
+    assert!(res.unwrap_err().to_string().ends_with(" in embedded.json"));
+
+    // The origin can still be renamed afterwards
+    let input = std::io::Cursor::new(r#"{ "debug": true }"#);
+    let c = Config::builder()
+        .add_source(
+            File::from_reader(input, FileFormat::Json, "embedded.json").origin("renamed.json"),
+        )
+        .build()
+        .unwrap();
+
+    assert_eq!(
+        c.get_with_origin::<bool>("debug").unwrap().1.as_deref(),
+        Some("renamed.json")
+    );
+}
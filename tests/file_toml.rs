@@ -3,6 +3,7 @@
 extern crate config;
 extern crate float_cmp;
 extern crate serde;
+extern crate toml;
 
 #[macro_use]
 extern crate serde_derive;
@@ -106,3 +107,179 @@ fn test_error_parse() {
         )
     );
 }
+
+#[test]
+fn test_error_parse_position() {
+    let err = Config::builder()
+        .add_source(File::new("tests/Settings-invalid", FileFormat::Toml))
+        .build()
+        .unwrap_err();
+
+    assert_eq!(err.position(), Some(Position { line: 2, column: 9 }));
+}
+
+#[test]
+fn test_error_parse_from_str_with_origin() {
+    let res = Config::builder()
+        .add_source(
+            File::from_str("ok = true\nerror = tru\n", FileFormat::Toml)
+                .origin("embedded:defaults.toml"),
+        )
+        .build();
+
+    assert!(res.is_err());
+    assert_eq!(
+        res.unwrap_err().to_string(),
+        "invalid TOML value, did you mean to use a quoted string? at line 2 column 9 in embedded:defaults.toml"
+            .to_string()
+    );
+}
+
+#[test]
+fn test_from_str_origin_in_type_error() {
+    let c = Config::builder()
+        .add_source(File::from_str("debug = \"maybe\"", FileFormat::Toml).origin("embedded"))
+        .build()
+        .unwrap();
+
+    assert_eq!(
+        c.get::<bool>("debug").unwrap_err().to_string(),
+        "invalid type: string \"maybe\", expected a boolean for key `debug` in embedded"
+            .to_string()
+    );
+}
+
+#[test]
+fn test_datetime_options() {
+    let text = "created = 1979-05-27T07:32:00Z";
+
+    let c = Config::builder()
+        .add_source(File::from_str(text, FileFormat::Toml))
+        .build()
+        .unwrap();
+
+    assert_eq!(
+        c.get::<Value>("created").unwrap().kind,
+        ValueKind::String("1979-05-27T07:32:00Z".into())
+    );
+
+    let c = Config::builder()
+        .add_source(
+            File::from_str(text, FileFormat::Toml)
+                .with_format_options(FormatOptions::new().toml_datetime(TomlDatetime::Typed)),
+        )
+        .build()
+        .unwrap();
+
+    assert!(matches!(
+        c.get::<Value>("created").unwrap().kind,
+        ValueKind::Table(_)
+    ));
+
+    let created: toml::value::Datetime = c.get("created").unwrap();
+    assert_eq!(created.to_string(), "1979-05-27T07:32:00Z");
+}
+
+#[test]
+fn test_root_key() {
+    let c = Config::builder()
+        .add_source(File::new("tests/Settings-apps", FileFormat::Toml).root_key("myapp"))
+        .build()
+        .unwrap();
+
+    assert_eq!(c.get("name").ok(), Some("myapp".to_string()));
+    assert_eq!(c.get("port").ok(), Some(8080));
+    assert_eq!(
+        c.get("database.url").ok(),
+        Some("postgres://localhost".to_string())
+    );
+    assert!(!c.contains_key("other"));
+    assert!(!c.contains_key("myapp"));
+}
+
+#[test]
+fn test_root_key_missing() {
+    let c = Config::builder()
+        .add_source(
+            File::new("tests/Settings-apps", FileFormat::Toml)
+                .root_key("missing")
+                .required(false),
+        )
+        .build()
+        .unwrap();
+    assert!(c.cache.into_table().unwrap().is_empty());
+
+    let res = Config::builder()
+        .add_source(File::new("tests/Settings-apps", FileFormat::Toml).root_key("missing"))
+        .build();
+
+    let path_with_extension: PathBuf = ["tests", "Settings-apps.toml"].iter().collect();
+    assert_eq!(
+        res.unwrap_err().to_string(),
+        format!(
+            "root key \"missing\" not found in {}",
+            path_with_extension.display()
+        )
+    );
+}
+
+#[test]
+fn test_kebab_to_snake() {
+    #[derive(Debug, Deserialize)]
+    struct Retry {
+        max_retries: u32,
+        backoff_ms: u64,
+        log_level: String,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct Settings {
+        retry: Retry,
+        dry_run: bool,
+    }
+
+    let s = r#"
+        dry-run = true
+
+        [retry]
+        max-retries = 3
+        backoff-ms = 250
+        log-level = "kebab"
+        log_level = "snake"
+    "#;
+
+    let c = Config::builder()
+        .add_source(File::from_str(s, FileFormat::Toml).kebab_to_snake(true))
+        .build()
+        .unwrap();
+
+    let settings: Settings = c.clone().try_into().unwrap();
+    assert!(settings.dry_run);
+    assert_eq!(settings.retry.max_retries, 3);
+    assert_eq!(settings.retry.backoff_ms, 250);
+    assert_eq!(settings.retry.log_level, "snake");
+    assert!(!c.contains_key("retry.max-retries"));
+
+    let c = Config::builder()
+        .add_source(File::from_str(s, FileFormat::Toml))
+        .build()
+        .unwrap();
+    assert_eq!(c.get::<u32>("retry.max-retries").unwrap(), 3);
+    assert!(!c.contains_key("retry.max_retries"));
+}
+
+#[test]
+#[cfg(feature = "preserve_order")]
+fn test_kebab_to_snake_keeps_order() {
+    let c = Config::builder()
+        .add_source(
+            File::from_str("zeta = 1\nmax-retries = 2\nalpha = 3", FileFormat::Toml)
+                .kebab_to_snake(true),
+        )
+        .build()
+        .unwrap();
+
+    let table = c.cache.into_table().unwrap();
+    let keys: Vec<&str> = table.keys().map(String::as_str).collect();
+    assert_eq!(keys, ["zeta", "max_retries", "alpha"]);
+}
@@ -96,3 +96,38 @@ fn test_error_parse() {
         )
     );
 }
+
+#[test]
+fn test_error_parse_position() {
+    let err = Config::builder()
+        .add_source(File::new("tests/Settings-invalid", FileFormat::Yaml))
+        .build()
+        .unwrap_err();
+
+    assert_eq!(err.position(), Some(Position { line: 2, column: 1 }));
+}
+
+#[test]
+fn test_yaml_multiple_documents() {
+    let text = "
+database:
+  host: localhost
+  port: 5432
+debug: false
+---
+database:
+  host: db.example.com
+";
+
+    let config = Config::builder()
+        .add_source(File::from_str(text, FileFormat::Yaml))
+        .build()
+        .unwrap();
+
+    assert_eq!(
+        config.get::<String>("database.host").unwrap(),
+        "db.example.com"
+    );
+    assert_eq!(config.get::<i64>("database.port").unwrap(), 5432);
+    assert!(!config.get::<bool>("debug").unwrap());
+}
@@ -0,0 +1,42 @@
+#![cfg(feature = "toml")]
+
+extern crate config;
+
+use config::*;
+
+#[test]
+fn test_fingerprint_ignores_sources_and_order() {
+    let from_file = Config::builder()
+        .add_source(File::from_str(
+            "name = \"app\"\n[server]\nport = 8080\nhosts = [\"a\", \"b\"]",
+            FileFormat::Toml,
+        ))
+        .build()
+        .unwrap();
+
+    let from_code = Config::builder()
+        .set_default("server.hosts", vec!["a", "b"])
+        .unwrap()
+        .set_default("server.port", 8080)
+        .unwrap()
+        .set_override("name", "app")
+        .unwrap()
+        .build()
+        .unwrap();
+
+    assert_eq!(from_file.fingerprint(), from_code.fingerprint());
+}
+
+#[test]
+fn test_fingerprint_changes_with_value() {
+    let build = |port: i64| {
+        Config::builder()
+            .set_default("server.port", port)
+            .unwrap()
+            .build()
+            .unwrap()
+    };
+
+    assert_eq!(build(8080).fingerprint(), build(8080).fingerprint());
+    assert_ne!(build(8080).fingerprint(), build(8081).fingerprint());
+}
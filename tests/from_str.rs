@@ -0,0 +1,87 @@
+extern crate config;
+extern crate serde;
+
+#[macro_use]
+extern crate serde_derive;
+
+use std::fmt;
+use std::net::Ipv4Addr;
+use std::str;
+
+use config::*;
+
+#[derive(Debug, PartialEq)]
+struct Rgb(u8, u8, u8);
+
+impl str::FromStr for Rgb {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let hex = s
+            .strip_prefix('#')
+            .filter(|hex| hex.len() == 6)
+            .ok_or_else(|| "expected a color like #rrggbb".to_string())?;
+        let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).map_err(|e| e.to_string());
+
+        Ok(Rgb(channel(0)?, channel(2)?, channel(4)?))
+    }
+}
+
+impl fmt::Display for Rgb {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "#{:02x}{:02x}{:02x}", self.0, self.1, self.2)
+    }
+}
+
+fn make() -> Config {
+    Config::builder()
+        .set_default("color", "#ff8000")
+        .unwrap()
+        .set_default("invalid", "orange")
+        .unwrap()
+        .set_default("ip", "127.0.0.1")
+        .unwrap()
+        .set_default("port", 8080)
+        .unwrap()
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn test_from_str() {
+    let config = make();
+
+    let color = config.get::<FromStr<Rgb>>("color").unwrap();
+    assert_eq!(color.into_inner(), Rgb(0xff, 0x80, 0x00));
+
+    let ip = config.get::<FromStr<Ipv4Addr>>("ip").unwrap();
+    assert!(ip.is_loopback());
+
+    // Scalars are parsed from their display form
+    let port = config.get::<FromStr<u16>>("port").unwrap();
+    assert_eq!(*port, 8080);
+}
+
+#[test]
+fn test_from_str_in_struct() {
+    #[derive(Debug, Deserialize)]
+    struct Settings {
+        color: FromStr<Rgb>,
+        ip: FromStr<Ipv4Addr>,
+    }
+
+    let settings: Settings = make().try_into().unwrap();
+
+    assert_eq!(settings.color.to_string(), "#ff8000");
+    assert_eq!(*settings.ip, Ipv4Addr::LOCALHOST);
+}
+
+#[test]
+fn test_from_str_error() {
+    let err = make().get::<FromStr<Rgb>>("invalid").unwrap_err();
+
+    assert_eq!(
+        err.to_string(),
+        "invalid value \"orange\": expected a color like #rrggbb"
+    );
+}
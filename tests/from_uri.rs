@@ -0,0 +1,70 @@
+#![cfg(feature = "toml")]
+
+extern crate config;
+
+use std::env;
+
+use config::source::from_uri;
+use config::*;
+
+#[test]
+fn test_from_uri_file() {
+    let source = from_uri("file://tests/Settings.toml").unwrap();
+    assert!(format!("{:?}", source).starts_with("File {"));
+    assert!(source.collect().unwrap()["debug"]
+        .clone()
+        .into_bool()
+        .unwrap());
+
+    let source = from_uri("file://tests/Settings?format=toml").unwrap();
+    assert!(format!("{:?}", source).contains("\"tests/Settings\""));
+    assert!(source.collect().unwrap().contains_key("place"));
+}
+
+#[test]
+fn test_from_uri_env() {
+    env::set_var("FROM_URI__PORT", "8080");
+    env::set_var("FROM_URI__DATABASE__URL", "postgres://db");
+
+    let config = Config::builder()
+        .add_source(vec![
+            from_uri("file://tests/Settings.toml").unwrap(),
+            from_uri("env://FROM_URI?separator=__").unwrap(),
+        ])
+        .build()
+        .unwrap();
+
+    assert_eq!(config.get::<String>("port").unwrap(), "8080");
+    assert_eq!(
+        config.get::<String>("database.url").unwrap(),
+        "postgres://db"
+    );
+    assert!(config.get::<bool>("debug").unwrap());
+
+    env::remove_var("FROM_URI__PORT");
+    env::remove_var("FROM_URI__DATABASE__URL");
+}
+
+#[test]
+fn test_from_uri_errors() {
+    assert_eq!(
+        from_uri("http://localhost/config.json")
+            .unwrap_err()
+            .to_string(),
+        "invalid source URI \"http://localhost/config.json\": unsupported scheme \"http\", expected `file` or `env`"
+    );
+    assert_eq!(
+        from_uri("tests/Settings.toml").unwrap_err().to_string(),
+        "invalid source URI \"tests/Settings.toml\": expected a scheme such as `file://`"
+    );
+    assert_eq!(
+        from_uri("file://tests/Settings?format=xml")
+            .unwrap_err()
+            .to_string(),
+        "invalid source URI \"file://tests/Settings?format=xml\": unknown format \"xml\""
+    );
+    assert_eq!(
+        from_uri("env://APP_?prefix=x").unwrap_err().to_string(),
+        "invalid source URI \"env://APP_?prefix=x\": unknown parameter \"prefix\""
+    );
+}
@@ -8,6 +8,7 @@ extern crate serde;
 extern crate serde_derive;
 
 use std::collections::HashSet;
+use std::time::Duration;
 
 use config::*;
 use float_cmp::ApproxEqUlps;
@@ -271,6 +272,33 @@ fn test_enum_key() {
     assert_eq!(s.quarks.len(), 6);
 }
 
+#[test]
+fn test_enum_case_insensitive() {
+    #[derive(Debug, Deserialize, PartialEq)]
+    enum Mode {
+        Fast,
+        Safe,
+    }
+
+    let c = Config::builder()
+        .set_default("mode", "FAST")
+        .unwrap()
+        .enum_case_insensitive(true)
+        .build()
+        .unwrap();
+    assert_eq!(c.get::<Mode>("mode").unwrap(), Mode::Fast);
+
+    let c = Config::builder()
+        .set_default("mode", "FAST")
+        .unwrap()
+        .build()
+        .unwrap();
+    assert_eq!(
+        c.get::<Mode>("mode").unwrap_err().to_string(),
+        "enum Mode does not have variant constructor FAST"
+    );
+}
+
 #[test]
 fn test_int_key() {
     #[derive(Debug, Deserialize, PartialEq)]
@@ -283,3 +311,607 @@ fn test_int_key() {
     assert_eq!(s.divisors[&4], 3);
     assert_eq!(s.divisors.len(), 4);
 }
+
+#[test]
+fn test_get_or_else_absent() {
+    let c = make();
+    let mut called = false;
+    let value: i64 = c
+        .get_or_else("not_found", || {
+            called = true;
+            42
+        })
+        .unwrap();
+
+    assert!(called);
+    assert_eq!(value, 42);
+}
+
+#[test]
+fn test_get_or_else_present() {
+    let c = make();
+    let mut called = false;
+    let value: i64 = c
+        .get_or_else("code", || {
+            called = true;
+            42
+        })
+        .unwrap();
+
+    assert!(!called);
+    assert_eq!(value, 53);
+}
+
+#[test]
+fn test_get_or_else_wrong_type() {
+    let c = make();
+    let mut called = false;
+    let res = c.get_or_else::<bool, _>("boolean_s_parse", || {
+        called = true;
+        true
+    });
+
+    assert!(!called);
+    assert!(res.is_err());
+    assert_eq!(
+        res.unwrap_err().to_string(),
+        "invalid type: string \"fals\", expected a boolean for key `boolean_s_parse` in tests/Settings.toml"
+            .to_string()
+    );
+}
+
+#[test]
+fn test_char() {
+    #[derive(Debug, Deserialize)]
+    struct Settings {
+        delimiter: char,
+    }
+
+    let c = Config::builder()
+        .add_source(File::from_str("delimiter = \",\"", FileFormat::Toml))
+        .build()
+        .unwrap();
+
+    let s: Settings = c.try_into().unwrap();
+    assert_eq!(s.delimiter, ',');
+}
+
+#[test]
+fn test_char_empty() {
+    let c = Config::builder()
+        .add_source(File::from_str("delimiter = \"\"", FileFormat::Toml))
+        .build()
+        .unwrap();
+
+    assert_eq!(
+        c.get::<char>("delimiter").unwrap_err().to_string(),
+        "invalid type: string \"\", expected a single character for key `delimiter`".to_string()
+    );
+}
+
+#[test]
+fn test_char_multiple() {
+    let c = Config::builder()
+        .add_source(File::from_str("delimiter = \"ab\"", FileFormat::Toml))
+        .build()
+        .unwrap();
+
+    assert_eq!(
+        c.get::<char>("delimiter").unwrap_err().to_string(),
+        "invalid type: string \"ab\", expected a single character for key `delimiter`".to_string()
+    );
+}
+
+#[test]
+fn test_get_string_lossy() {
+    let c = make();
+
+    assert_eq!(c.get_string_lossy("code"), Some("53".to_string()));
+    assert_eq!(c.get_string_lossy("debug"), Some("true".to_string()));
+    assert_eq!(c.get_string_lossy("place.rating"), Some("4.5".to_string()));
+    assert_eq!(
+        c.get_string_lossy("place.name"),
+        Some("Torre di Pisa".to_string())
+    );
+    assert_eq!(c.get_string_lossy("not_found"), None);
+    assert!(c.get_string_lossy("arr").is_some());
+}
+
+#[test]
+fn test_try_deserialize_key() {
+    let c = make();
+
+    let p: Place = c.try_deserialize_key("place").unwrap();
+    assert_eq!(p.name, "Torre di Pisa");
+    assert_eq!(p.reviews, 3866);
+
+    assert!(matches!(
+        c.try_deserialize_key::<Place>("not_a_section"),
+        Err(ConfigError::NotFound(_))
+    ));
+    assert!(matches!(
+        c.try_deserialize_key::<Place>("debug"),
+        Err(ConfigError::Type { .. })
+    ));
+}
+
+#[test]
+fn test_shared_config() {
+    let shared = make().into_shared();
+    let clone = shared.clone();
+
+    assert!(SharedConfig::ptr_eq(&shared, &clone));
+    assert_eq!(clone.get::<bool>("debug").ok(), Some(true));
+    assert_eq!(clone.get_string("place.name").unwrap(), "Torre di Pisa");
+
+    let s: Settings = shared.try_deserialize().unwrap();
+    assert_eq!(s.place.name, "Torre di Pisa");
+
+    let handle = std::thread::spawn(move || clone.get_int("place.reviews").unwrap());
+    assert_eq!(handle.join().unwrap(), 3866);
+}
+
+#[test]
+fn test_get_table_keys() {
+    let c = Config::builder()
+        .add_source(File::from_str(
+            r#"
+            debug = true
+
+            [feature.b]
+            enabled = false
+
+            [feature.a]
+            enabled = true
+            "#,
+            FileFormat::Toml,
+        ))
+        .build()
+        .unwrap();
+
+    assert_eq!(c.get_table_keys("feature").unwrap(), vec!["a", "b"]);
+    assert_eq!(c.get_table_keys("feature.a").unwrap(), vec!["enabled"]);
+    assert!(matches!(
+        c.get_table_keys("missing"),
+        Err(ConfigError::NotFound(_))
+    ));
+
+    let err = c.get_table_keys("debug").unwrap_err();
+    assert_eq!(err.kind(), ConfigErrorKind::Type);
+    assert_eq!(
+        err.to_string(),
+        "invalid type: boolean `true`, expected a map for key `debug`"
+    );
+}
+
+#[test]
+fn test_into_flat_string_map() {
+    let c = Config::builder()
+        .add_source(File::from_str(
+            r#"
+            debug = true
+
+            [database]
+            url = "postgres://localhost"
+            pool = { size = 4, timeout = 0.5 }
+
+            [[servers]]
+            host = "a"
+            ports = [80, 443]
+
+            [[servers]]
+            host = "b"
+            "#,
+            FileFormat::Toml,
+        ))
+        .build()
+        .unwrap();
+
+    let flat = c.clone().into_flat_string_map();
+
+    assert_eq!(flat.len(), 8);
+    assert_eq!(flat["debug"], "true");
+    assert_eq!(flat["database.url"], "postgres://localhost");
+    assert_eq!(flat["database.pool.size"], "4");
+    assert_eq!(flat["database.pool.timeout"], "0.5");
+    assert_eq!(flat["servers[0].host"], "a");
+    assert_eq!(flat["servers[0].ports[1]"], "443");
+    assert_eq!(flat["servers[1].host"], "b");
+
+    for key in flat.keys() {
+        assert!(c.get::<Value>(key).is_ok(), "{} is not a valid path", key);
+    }
+}
+
+#[test]
+fn test_get_duration() {
+    let config = Config::builder()
+        .set_default("timeout", 30)
+        .unwrap()
+        .set_default("delay", 1.5)
+        .unwrap()
+        .set_default("interval", "250ms")
+        .unwrap()
+        .set_default("ttl", "2h")
+        .unwrap()
+        .set_default("plain", "45")
+        .unwrap()
+        .set_default("bad", "10 parsecs")
+        .unwrap()
+        .build()
+        .unwrap();
+
+    assert_eq!(
+        config.get_duration("timeout").unwrap(),
+        Duration::from_secs(30)
+    );
+    assert_eq!(
+        config.get_duration("delay").unwrap(),
+        Duration::from_millis(1500)
+    );
+    assert_eq!(
+        config.get_duration("interval").unwrap(),
+        Duration::from_millis(250)
+    );
+    assert_eq!(
+        config.get_duration("ttl").unwrap(),
+        Duration::from_secs(7200)
+    );
+    assert_eq!(
+        config.get_duration("plain").unwrap(),
+        Duration::from_secs(45)
+    );
+
+    let err = config.get_duration("bad").unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "invalid type: string \"10 parsecs\", expected a duration such as 30 or \"30s\" for key `bad`"
+    );
+}
+
+#[test]
+fn test_get_bytes() {
+    let config = Config::builder()
+        .set_default("plain", 512)
+        .unwrap()
+        .set_default("decimal", "10MB")
+        .unwrap()
+        .set_default("binary", "4 KiB")
+        .unwrap()
+        .set_default("lowercase", "1.5gb")
+        .unwrap()
+        .set_default("negative", -1)
+        .unwrap()
+        .set_default("bad", "10XB")
+        .unwrap()
+        .build()
+        .unwrap();
+
+    assert_eq!(config.get_bytes("plain").unwrap(), 512);
+    assert_eq!(config.get_bytes("decimal").unwrap(), 10_000_000);
+    assert_eq!(config.get_bytes("binary").unwrap(), 4096);
+    assert_eq!(config.get_bytes("lowercase").unwrap(), 1_500_000_000);
+    assert!(config.get_bytes("negative").is_err());
+
+    let err = config.get_bytes("bad").unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "invalid type: string \"10XB\", expected a size in bytes such as 1024 or \"10MB\" for key `bad`"
+    );
+}
+
+#[test]
+fn test_option_nil_and_absent() {
+    #[derive(Debug, Deserialize)]
+    struct Settings {
+        present: Option<String>,
+        null: Option<String>,
+        absent: Option<String>,
+    }
+
+    let config = Config::builder()
+        .set_default("present", "value")
+        .unwrap()
+        .set_default("null", None::<String>)
+        .unwrap()
+        .build()
+        .unwrap();
+
+    let s: Settings = config.try_into().unwrap();
+
+    assert_eq!(s.present, Some("value".to_string()));
+    assert_eq!(s.null, None);
+    assert_eq!(s.absent, None);
+}
+
+#[test]
+fn test_double_option() {
+    #[derive(Debug, Deserialize)]
+    struct Settings {
+        #[serde(default, deserialize_with = "config::double_option")]
+        present: Option<Option<String>>,
+        #[serde(default, deserialize_with = "config::double_option")]
+        null: Option<Option<String>>,
+        #[serde(default, deserialize_with = "config::double_option")]
+        absent: Option<Option<String>>,
+    }
+
+    let config = Config::builder()
+        .set_default("present", "value")
+        .unwrap()
+        .set_default("null", None::<String>)
+        .unwrap()
+        .build()
+        .unwrap();
+
+    let s: Settings = config.try_into().unwrap();
+
+    assert_eq!(s.present, Some(Some("value".to_string())));
+    assert_eq!(s.null, Some(None));
+    assert_eq!(s.absent, None);
+}
+
+#[test]
+fn test_as_value() {
+    let c = make();
+    let root = c.as_value();
+
+    let diodes = match root.kind {
+        ValueKind::Table(ref table) => &table["diodes"],
+        _ => panic!("the root is not a table"),
+    };
+    let red = match diodes.kind {
+        ValueKind::Table(ref table) => &table["red"],
+        _ => panic!("diodes is not a table"),
+    };
+    let brightness = match red.kind {
+        ValueKind::Table(ref table) => table["brightness"].clone(),
+        _ => panic!("red is not a table"),
+    };
+
+    assert!(brightness.origin().unwrap().ends_with("Settings.toml"));
+    assert_eq!(brightness.into_int().unwrap(), 100);
+}
+
+#[test]
+fn test_unit_from_nil() {
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Marker;
+
+    #[derive(Debug, Deserialize)]
+    struct Settings {
+        unit: (),
+        marker: Marker,
+        section: Marker,
+    }
+
+    let config = Config::builder()
+        .set_default("unit", None::<String>)
+        .unwrap()
+        .set_default("marker", None::<String>)
+        .unwrap()
+        .set_default("section", Map::<String, Value>::new())
+        .unwrap()
+        .set_default("name", "value")
+        .unwrap()
+        .build()
+        .unwrap();
+
+    let s: Settings = config.clone().try_into().unwrap();
+    assert_eq!(s.unit, ());
+    assert_eq!(s.marker, Marker);
+    assert_eq!(s.section, Marker);
+
+    assert!(config.get::<()>("name").is_err());
+    assert!(config.get::<Marker>("name").is_err());
+}
+
+#[test]
+fn test_contains_key() {
+    let c = Config::builder()
+        .add_source(File::new("tests/Settings", FileFormat::Toml))
+        .set_override("nothing", None::<String>)
+        .unwrap()
+        .build()
+        .unwrap();
+
+    assert!(c.contains_key("debug"));
+    assert!(c.contains_key("nothing"));
+    assert!(c.contains_key("place.creator.name"));
+    assert!(c.contains_key("arr[2]"));
+
+    assert!(!c.contains_key("place.missing"));
+    assert!(!c.contains_key("arr[20]"));
+    assert!(!c.contains_key("debug.nested"));
+}
+
+#[test]
+fn test_get_raw() {
+    let c = Config::builder()
+        .add_source(File::new("tests/Settings", FileFormat::Toml))
+        .build()
+        .unwrap();
+
+    let code = c.get_raw("code").unwrap();
+    assert_eq!(code.kind, ValueKind::Integer(53));
+    assert_eq!(code.origin(), Some("tests/Settings.toml"));
+
+    let debug_s = c.get_raw("debug_s").unwrap();
+    assert_eq!(debug_s.kind, ValueKind::String("true".into()));
+
+    let red = c.get_raw("diodes.red").unwrap();
+    let table = match red.kind {
+        ValueKind::Table(table) => table,
+        kind => panic!("expected a table, got {:?}", kind),
+    };
+    assert_eq!(table["brightness"].kind, ValueKind::Integer(100));
+    assert_eq!(table["brightness"].origin(), Some("tests/Settings.toml"));
+
+    assert!(matches!(
+        c.get_raw("diodes.missing"),
+        Err(ConfigError::NotFound(_))
+    ));
+}
+
+#[test]
+fn test_subset() {
+    let c = make();
+
+    let diodes = c.subset("diodes").unwrap();
+    assert_eq!(diodes.get::<String>("green").unwrap(), "off");
+    assert_eq!(diodes.get::<i64>("red.brightness").unwrap(), 100);
+    assert_eq!(diodes.get_array("blue.blinking").unwrap().len(), 2);
+    assert!(!diodes.contains_key("diodes"));
+    assert!(!diodes.contains_key("debug"));
+
+    let pattern = diodes.subset("white.pattern").unwrap();
+    assert_eq!(pattern.get::<String>("name").unwrap(), "christmas");
+
+    assert!(matches!(
+        c.subset("diodes.missing"),
+        Err(ConfigError::NotFound(_))
+    ));
+    assert!(matches!(c.subset("code"), Err(ConfigError::Type { .. })));
+}
+
+#[test]
+fn test_get_with_separator() {
+    let c = Config::builder()
+        .add_source(File::from_str(
+            r#"
+            [sites."a.com"]
+            port = 8080
+            "#,
+            FileFormat::Toml,
+        ))
+        .build()
+        .unwrap();
+
+    assert_eq!(
+        c.get_with_separator::<u16>("sites/a.com/port", '/')
+            .unwrap(),
+        8080
+    );
+    assert!(c.get::<u16>("sites.a.com.port").is_err());
+}
+
+#[test]
+fn test_deserialize_seed() {
+    use serde::de::{Deserialize, DeserializeSeed, Deserializer};
+
+    // Resolves plugin names to their index in the registry
+    struct Registry(Vec<&'static str>);
+
+    impl<'de> DeserializeSeed<'de> for &Registry {
+        type Value = Vec<usize>;
+
+        fn deserialize<D: Deserializer<'de>>(
+            self,
+            deserializer: D,
+        ) -> Result<Vec<usize>, D::Error> {
+            #[derive(Deserialize)]
+            struct Plugins {
+                plugins: Vec<String>,
+            }
+
+            let plugins = Plugins::deserialize(deserializer)?.plugins;
+            plugins
+                .iter()
+                .map(|name| {
+                    self.0
+                        .iter()
+                        .position(|plugin| plugin == name)
+                        .ok_or_else(|| serde::de::Error::custom(format!("unknown plugin {}", name)))
+                })
+                .collect()
+        }
+    }
+
+    let c = Config::builder()
+        .set_default("plugins", vec!["metrics", "auth"])
+        .unwrap()
+        .build()
+        .unwrap();
+
+    let registry = Registry(vec!["auth", "cache", "metrics"]);
+    assert_eq!(c.deserialize_seed(&registry).unwrap(), [2, 0]);
+
+    let registry = Registry(vec!["auth"]);
+    assert_eq!(
+        c.deserialize_seed(&registry).unwrap_err().to_string(),
+        "unknown plugin metrics"
+    );
+}
+
+#[test]
+fn test_float_from_integer() {
+    let c = Config::builder()
+        .set_default("rate", 5)
+        .unwrap()
+        .set_default("exact", 16_777_216)
+        .unwrap()
+        .set_default("inexact", 16_777_217)
+        .unwrap()
+        .build()
+        .unwrap();
+
+    assert_eq!(c.get::<f64>("rate").unwrap(), 5.0);
+    assert_eq!(c.get::<f32>("rate").unwrap(), 5.0);
+    assert_eq!(c.get::<f32>("exact").unwrap(), 16_777_216.0);
+    assert_eq!(c.get::<f64>("inexact").unwrap(), 16_777_217.0);
+
+    assert_eq!(
+        c.get::<f32>("inexact").unwrap_err().to_string(),
+        "invalid type: integer `16777217`, expected a number representable as a 32-bit floating point for key `inexact`"
+    );
+}
+
+#[test]
+fn test_typed() {
+    #[derive(Debug, Deserialize)]
+    struct Settings {
+        debug: f64,
+        place: Place,
+    }
+
+    let mut c = make();
+
+    let first = c.typed::<Settings>().unwrap();
+    let second = c.typed::<Settings>().unwrap();
+    assert!(std::sync::Arc::ptr_eq(&first, &second));
+    assert_eq!(first.place.name, "Torre di Pisa");
+
+    #[allow(deprecated)]
+    c.set("place.name", "Colosseo").unwrap();
+
+    let third = c.typed::<Settings>().unwrap();
+    assert!(!std::sync::Arc::ptr_eq(&first, &third));
+    assert_eq!(third.place.name, "Colosseo");
+    assert_eq!(third.debug, first.debug);
+}
+
+#[test]
+fn test_try_deserialize_with_base() {
+    #[derive(Debug, Deserialize)]
+    struct Tenant {
+        name: String,
+        place: Place,
+    }
+
+    let base = make();
+    let tenant = Config::builder()
+        .set_default("name", "acme")
+        .unwrap()
+        .set_default("place.name", "Acme HQ")
+        .unwrap()
+        .build()
+        .unwrap();
+
+    let s: Tenant = tenant.try_deserialize_with_base(&base).unwrap();
+    assert_eq!(s.name, "acme");
+    assert_eq!(s.place.name, "Acme HQ");
+    // Absent in the tenant
+    assert_eq!(s.place.reviews, 3866);
+
+    assert!(tenant.try_into::<Tenant>().is_err());
+}
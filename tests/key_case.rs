@@ -0,0 +1,103 @@
+#![cfg(all(feature = "json", feature = "toml"))]
+
+extern crate config;
+extern crate serde;
+
+#[macro_use]
+extern crate serde_derive;
+
+use config::*;
+
+#[derive(Debug, Deserialize)]
+struct Retry {
+    max_retries: u32,
+    backoff_ms: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct Settings {
+    retry_policy: Retry,
+}
+
+#[test]
+fn test_camel_case_keys() {
+    let c = Config::builder()
+        .add_source(File::from_str(
+            r#"{ "retryPolicy": { "maxRetries": 3, "backoffMs": 250 } }"#,
+            FileFormat::Json,
+        ))
+        .key_case(KeyCase::CamelToSnake)
+        .build()
+        .unwrap();
+
+    let s: Settings = c.try_into().unwrap();
+    assert_eq!(s.retry_policy.max_retries, 3);
+    assert_eq!(s.retry_policy.backoff_ms, 250);
+}
+
+#[test]
+fn test_kebab_case_keys() {
+    let c = Config::builder()
+        .add_source(File::from_str(
+            "[retry-policy]\nmax-retries = 5\nbackoff-ms = 100\n",
+            FileFormat::Toml,
+        ))
+        .key_case(KeyCase::KebabToSnake)
+        .build()
+        .unwrap();
+
+    let s: Settings = c.try_into().unwrap();
+    assert_eq!(s.retry_policy.max_retries, 5);
+    assert_eq!(s.retry_policy.backoff_ms, 100);
+}
+
+#[test]
+fn test_layer_precedence_is_kept() {
+    let c = Config::builder()
+        .set_default("retry_policy.max_retries", 1)
+        .unwrap()
+        .set_default("retryPolicy.backoffMs", 10)
+        .unwrap()
+        .add_source(File::from_str(
+            r#"{ "retryPolicy": { "maxRetries": 3 } }"#,
+            FileFormat::Json,
+        ))
+        .key_case(KeyCase::CamelToSnake)
+        .build()
+        .unwrap();
+
+    assert_eq!(c.get::<u32>("retry_policy.max_retries").unwrap(), 3);
+    assert_eq!(c.get::<u64>("retry_policy.backoff_ms").unwrap(), 10);
+}
+
+#[test]
+fn test_snake_case_key_wins_collision() {
+    let c = Config::builder()
+        .add_source(File::from_str(
+            r#"{ "maxRetries": 3, "max_retries": 7 }"#,
+            FileFormat::Json,
+        ))
+        .key_case(KeyCase::CamelToSnake)
+        .build()
+        .unwrap();
+
+    assert_eq!(c.get::<u32>("max_retries").unwrap(), 7);
+}
+
+#[test]
+#[cfg(feature = "preserve_order")]
+fn test_key_case_keeps_order() {
+    let c = Config::builder()
+        .add_source(File::from_str(
+            "zeta = 1\nmax-retries = 2\nlog-level = \"kebab\"\nalpha = 3\nlog_level = \"snake\"",
+            FileFormat::Toml,
+        ))
+        .key_case(KeyCase::KebabToSnake)
+        .build()
+        .unwrap();
+
+    let table = c.cache.clone().into_table().unwrap();
+    let keys: Vec<&str> = table.keys().map(String::as_str).collect();
+    assert_eq!(keys, ["zeta", "max_retries", "log_level", "alpha"]);
+    assert_eq!(c.get::<String>("log_level").unwrap(), "snake");
+}
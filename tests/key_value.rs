@@ -0,0 +1,62 @@
+extern crate config;
+
+use std::collections::BTreeMap;
+
+use config::*;
+
+struct Store(BTreeMap<String, String>);
+
+impl KvBackend for Store {
+    fn scan_prefix(&self, prefix: &str) -> Vec<(String, String)> {
+        self.0
+            .range(prefix.to_string()..)
+            .take_while(|(key, _)| key.starts_with(prefix))
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect()
+    }
+}
+
+fn store() -> Store {
+    let pairs = [
+        ("app/debug", "true"),
+        ("app/database/url", "postgres://db"),
+        ("app/database/pool", "8"),
+        ("other/debug", "false"),
+    ];
+
+    Store(
+        pairs
+            .iter()
+            .map(|&(key, value)| (key.to_string(), value.to_string()))
+            .collect(),
+    )
+}
+
+#[test]
+fn test_key_value_nesting() {
+    let config = Config::builder()
+        .add_source(
+            KeyValue::new(store(), "app/")
+                .separator("/")
+                .try_parsing(true),
+        )
+        .build()
+        .unwrap();
+
+    assert!(config.get::<bool>("debug").unwrap());
+    assert_eq!(
+        config.get::<String>("database.url").unwrap(),
+        "postgres://db"
+    );
+    assert_eq!(config.get::<i64>("database.pool").unwrap(), 8);
+    assert!(!config.contains_key("other"));
+}
+
+#[test]
+fn test_key_value_strings() {
+    let m = KeyValue::new(store(), "app/").collect().unwrap();
+
+    assert_eq!(m.len(), 3);
+    assert_eq!(m["database/pool"].clone().into_string().unwrap(), "8");
+    assert_eq!(m["debug"].origin(), Some("the key-value store"));
+}
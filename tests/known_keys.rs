@@ -0,0 +1,89 @@
+extern crate config;
+extern crate serde;
+
+#[macro_use]
+extern crate serde_derive;
+
+use config::*;
+
+#[derive(Debug, Clone)]
+struct InMemory(Map<String, Value>);
+
+impl InMemory {
+    fn new(entries: &[(&str, &str)]) -> Self {
+        InMemory(
+            entries
+                .iter()
+                .map(|&(key, value)| (key.to_string(), value.into()))
+                .collect(),
+        )
+    }
+}
+
+impl Source for InMemory {
+    fn clone_into_box(&self) -> Box<dyn Source + Send + Sync> {
+        Box::new(self.clone())
+    }
+
+    fn collect(&self) -> Result<Map<String, Value>, ConfigError> {
+        Ok(self.0.clone())
+    }
+
+    fn known_keys(&self) -> Option<Vec<String>> {
+        Some(self.0.keys().cloned().collect())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[allow(dead_code)]
+struct Database {
+    url: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[allow(dead_code)]
+struct Settings {
+    name: String,
+    database: Database,
+    debug: Option<bool>,
+}
+
+#[test]
+fn test_known_keys() {
+    let source = InMemory::new(&[("name", "app"), ("database.url", "postgres")]);
+
+    let mut keys = source.known_keys().unwrap();
+    keys.sort();
+    assert_eq!(keys, vec!["database.url", "name"]);
+
+    assert!(Environment::new().known_keys().is_none());
+    assert!(vec![source.clone(), source].known_keys().is_some());
+}
+
+#[test]
+fn test_check_required() {
+    let builder = Config::builder()
+        .add_source(InMemory::new(&[("database.url", "postgres")]))
+        .set_default("name", "app")
+        .unwrap();
+
+    assert!(builder.check_required::<Settings>().is_ok());
+
+    let builder = Config::builder().add_source(InMemory::new(&[("debug", "true")]));
+
+    let err = builder.check_required::<Settings>().unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "no source provides the required fields: \"name\", \"database\""
+    );
+}
+
+#[test]
+fn test_check_required_unknown_keys() {
+    // The keys of the environment are unknown, so nothing can be reported missing
+    let builder = Config::builder()
+        .add_source(InMemory::new(&[("debug", "true")]))
+        .add_source(Environment::with_prefix("KNOWN_KEYS_TEST"));
+
+    assert!(builder.check_required::<Settings>().is_ok());
+}
@@ -0,0 +1,43 @@
+#![cfg(feature = "toml")]
+
+extern crate config;
+
+use config::*;
+
+#[test]
+fn test_layers_override_in_order() {
+    let config = Config::builder()
+        .add_layers(
+            &[
+                "tests/layers/base.toml",
+                "tests/layers/local.toml",
+                "tests/layers/secret.toml",
+            ],
+            FileFormat::Toml,
+        )
+        .build()
+        .unwrap();
+
+    assert_eq!(config.get("name").ok(), Some("app".to_string()));
+    assert_eq!(config.get("debug").ok(), Some(true));
+    assert_eq!(config.get("port").ok(), Some(443));
+    assert_eq!(config.get("password").ok(), Some("hunter2".to_string()));
+}
+
+#[test]
+fn test_layers_missing_file() {
+    let config = Config::builder()
+        .add_layers(
+            &[
+                "tests/layers/base.toml",
+                "tests/layers/missing.toml",
+                "tests/layers/secret.toml",
+            ],
+            FileFormat::Toml,
+        )
+        .build()
+        .unwrap();
+
+    assert_eq!(config.get("debug").ok(), Some(false));
+    assert_eq!(config.get("port").ok(), Some(443));
+}
@@ -0,0 +1,113 @@
+extern crate config;
+
+#[macro_use]
+extern crate serde_derive;
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::collections::HashMap;
+
+use config::*;
+
+/// Counts the allocations of the current thread, tests running in parallel.
+struct Counting;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = Cell::new(0);
+}
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.with(|count| count.set(count.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+fn allocations<T>(f: impl FnOnce() -> T) -> (T, usize) {
+    let before = ALLOCATIONS.with(Cell::get);
+    let result = f();
+    (result, ALLOCATIONS.with(Cell::get) - before)
+}
+
+fn large_table(len: usize) -> Value {
+    let table: Map<String, Value> = (0..len)
+        .map(|i| (format!("key{}", i), Value::from(i as i64)))
+        .collect();
+    table.into()
+}
+
+#[test]
+fn test_map_keys() {
+    let value = large_table(100);
+    let map: HashMap<String, i64> = value.try_into().unwrap();
+
+    assert_eq!(map.len(), 100);
+    for i in 0..100 {
+        assert_eq!(map[&format!("key{}", i)], i as i64);
+    }
+}
+
+#[test]
+fn test_map_keys_parsed() {
+    #[derive(Debug, Deserialize, PartialEq, Eq, Hash)]
+    #[serde(rename_all = "lowercase")]
+    enum Level {
+        Low,
+        High,
+    }
+
+    let mut table = Map::new();
+    table.insert("low".to_string(), Value::from(1));
+    table.insert("high".to_string(), Value::from(2));
+    let levels: HashMap<Level, i64> = Value::from(table).try_into().unwrap();
+    assert_eq!(levels[&Level::Low], 1);
+    assert_eq!(levels[&Level::High], 2);
+
+    let mut table = Map::new();
+    table.insert("10".to_string(), Value::from("ten"));
+    let ports: HashMap<u16, String> = Value::from(table).try_into().unwrap();
+    assert_eq!(ports[&10], "ten".to_string());
+}
+
+#[test]
+fn test_map_keys_allocations() {
+    // Every key is copied once, by the map receiving it
+    let value = large_table(1000);
+    let (_, cloning) = allocations(|| value.clone());
+    let (map, total) = allocations(|| value.clone().try_into::<HashMap<String, i64>>().unwrap());
+
+    assert_eq!(map.len(), 1000);
+    assert!(total - cloning < 1100, "{} allocations", total - cloning);
+}
+
+#[test]
+fn test_struct_keys_allocations() {
+    #[derive(Debug, Deserialize)]
+    struct Settings {
+        host: String,
+        port: u16,
+        debug: bool,
+    }
+
+    let mut table = Map::new();
+    table.insert("host".to_string(), Value::from("localhost"));
+    table.insert("port".to_string(), Value::from(8080));
+    table.insert("debug".to_string(), Value::from(true));
+    let value = Value::from(table);
+
+    // Fields are matched without copying the keys, the only allocation buffers the entries
+    let (_, cloning) = allocations(|| value.clone());
+    let (settings, total) = allocations(|| value.clone().try_into::<Settings>().unwrap());
+
+    assert_eq!(settings.host, "localhost".to_string());
+    assert_eq!(settings.port, 8080);
+    assert!(settings.debug);
+    assert!(total - cloning <= 1, "{} allocations", total - cloning);
+}
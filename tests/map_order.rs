@@ -0,0 +1,132 @@
+#![cfg(feature = "json")]
+
+extern crate config;
+extern crate serde;
+
+#[macro_use]
+extern crate serde_derive;
+
+use std::fmt;
+
+use config::*;
+use serde::de::{Deserialize, Deserializer, MapAccess, Visitor};
+
+/// Keys of a table in the order they are deserialized.
+#[derive(Debug, PartialEq)]
+struct KeyOrder(Vec<String>);
+
+impl<'de> Deserialize<'de> for KeyOrder {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct KeyOrderVisitor;
+
+        impl<'de> Visitor<'de> for KeyOrderVisitor {
+            type Value = KeyOrder;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a table")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<KeyOrder, A::Error> {
+                let mut keys = Vec::new();
+                while let Some((key, _)) = map.next_entry::<String, Value>()? {
+                    keys.push(key);
+                }
+                Ok(KeyOrder(keys))
+            }
+        }
+
+        deserializer.deserialize_map(KeyOrderVisitor)
+    }
+}
+
+/// Fields serialized in an order that is neither sorted nor reversed.
+#[derive(Serialize)]
+struct Fields {
+    zeta: i64,
+    alpha: i64,
+    mu: i64,
+}
+
+fn serialized() -> Config {
+    Config::try_from(&Fields {
+        zeta: 1,
+        alpha: 2,
+        mu: 3,
+    })
+    .unwrap()
+}
+
+fn make() -> Config {
+    Config::builder()
+        .add_source(File::from_str(
+            r#"{"zeta": 1, "alpha": 2, "mu": 3, "beta": 4, "omega": 5}"#,
+            FileFormat::Json,
+        ))
+        .build()
+        .unwrap()
+}
+
+#[test]
+#[cfg(not(feature = "preserve_order"))]
+fn test_sorted_deserialization_order() {
+    for _ in 0..10 {
+        let order: KeyOrder = make().try_into().unwrap();
+        assert_eq!(
+            order,
+            KeyOrder(vec![
+                "alpha".into(),
+                "beta".into(),
+                "mu".into(),
+                "omega".into(),
+                "zeta".into()
+            ])
+        );
+    }
+}
+
+#[test]
+#[cfg(feature = "preserve_order")]
+fn test_insertion_deserialization_order() {
+    let order: KeyOrder = make().try_into().unwrap();
+    assert_eq!(
+        order,
+        KeyOrder(vec![
+            "zeta".into(),
+            "alpha".into(),
+            "mu".into(),
+            "beta".into(),
+            "omega".into()
+        ])
+    );
+}
+
+#[test]
+#[cfg(not(feature = "preserve_order"))]
+fn test_sorted_serialization_order() {
+    let order: KeyOrder = serialized().try_into().unwrap();
+    assert_eq!(
+        order,
+        KeyOrder(vec!["alpha".into(), "mu".into(), "zeta".into()])
+    );
+}
+
+#[test]
+#[cfg(feature = "preserve_order")]
+fn test_insertion_serialization_order() {
+    let config = serialized();
+
+    let keys: Vec<String> = config
+        .cache
+        .clone()
+        .into_table()
+        .unwrap()
+        .into_keys()
+        .collect();
+    assert_eq!(keys, ["zeta", "alpha", "mu"]);
+
+    let order: KeyOrder = config.try_into().unwrap();
+    assert_eq!(
+        order,
+        KeyOrder(vec!["zeta".into(), "alpha".into(), "mu".into()])
+    );
+}
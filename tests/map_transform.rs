@@ -0,0 +1,111 @@
+extern crate config;
+
+use config::*;
+
+#[test]
+fn test_map_transform_adds_derived_key() {
+    let config = Config::builder()
+        .set_default("host", "localhost")
+        .unwrap()
+        .set_override("port", 8080)
+        .unwrap()
+        .map_transform(|mut map| {
+            let address = format!(
+                "{}:{}",
+                map["host"].clone().into_string()?,
+                map["port"].clone().into_int()?
+            );
+            map.insert("address".into(), address.into());
+            Ok(map)
+        })
+        .build()
+        .unwrap();
+
+    assert_eq!(
+        config.get_string("address").unwrap(),
+        "localhost:8080".to_string()
+    );
+}
+
+#[test]
+fn test_map_transforms_run_in_order() {
+    let config = Config::builder()
+        .set_default("internal", true)
+        .unwrap()
+        .map_transform(|mut map| {
+            map.insert("count".into(), 1.into());
+            Ok(map)
+        })
+        .map_transform(|mut map| {
+            map.remove("internal");
+            let count = map["count"].clone().into_int()?;
+            map.insert("count".into(), (count + 1).into());
+            Ok(map)
+        })
+        .build()
+        .unwrap();
+
+    assert_eq!(config.get_int("count").unwrap(), 2);
+    assert!(!config.contains_key("internal"));
+}
+
+#[test]
+fn test_map_transform_error() {
+    let res = Config::builder()
+        .map_transform(|_| Err(ConfigError::Message("rejected".into())))
+        .build();
+
+    assert_eq!(res.unwrap_err().to_string(), "rejected".to_string());
+}
+
+#[test]
+fn test_require_one_of() {
+    let keys = &["database.url", "database.host"];
+
+    let res = Config::builder()
+        .set_default("database.port", 5432)
+        .unwrap()
+        .require_one_of(keys)
+        .build();
+    assert_eq!(
+        res.unwrap_err().to_string(),
+        "none of the keys \"database.url\", \"database.host\" is set"
+    );
+
+    let res = Config::builder()
+        .set_default("database.url", "postgres://db")
+        .unwrap()
+        .set_override("database.host", "db")
+        .unwrap()
+        .require_one_of(keys)
+        .build();
+    assert_eq!(
+        res.unwrap_err().to_string(),
+        "only one of the keys \"database.url\", \"database.host\" can be set, but \"database.url\", \"database.host\" are"
+    );
+
+    let config = Config::builder()
+        .set_default("database.host", "db")
+        .unwrap()
+        .require_one_of(keys)
+        .build()
+        .unwrap();
+    assert_eq!(config.get_string("database.host").unwrap(), "db");
+}
+
+#[test]
+fn test_require_any_of() {
+    let config = Config::builder()
+        .set_default("database.url", "postgres://db")
+        .unwrap()
+        .set_default("database.host", "db")
+        .unwrap()
+        .require_any_of(&["database.url", "database.host"])
+        .build();
+    assert!(config.is_ok());
+
+    let res = Config::builder()
+        .require_any_of(&["database.url", "database.host"])
+        .build();
+    assert!(res.is_err());
+}
@@ -57,3 +57,182 @@ fn test_merge_whole_config() {
     assert_eq!(config3.get("x").ok(), Some(10));
     assert_eq!(config3.get("y").ok(), Some(25));
 }
+
+#[test]
+fn test_merge_array_element_origins() {
+    let mut vars = Map::new();
+    vars.insert("HOSTS[2]".to_string(), "c".to_string());
+
+    let c = Config::builder()
+        .add_source(File::from_str(r#"hosts = ["a", "b"]"#, FileFormat::Toml).origin("base.toml"))
+        .add_source(Environment::new().source(Some(vars)))
+        .build()
+        .unwrap();
+
+    let hosts = c.get_array("hosts").unwrap();
+    let origins: Vec<_> = hosts.iter().map(|host| host.origin()).collect();
+    assert_eq!(
+        origins,
+        vec![
+            Some("base.toml"),
+            Some("base.toml"),
+            Some("the environment")
+        ]
+    );
+
+    let hosts: Vec<String> = hosts
+        .into_iter()
+        .map(|host| host.into_string().unwrap())
+        .collect();
+    assert_eq!(hosts, vec!["a", "b", "c"]);
+}
+
+#[test]
+fn test_merge_strict_types() {
+    let res = Config::builder()
+        .add_source(File::from_str("[server]\ndebug = true", FileFormat::Toml).origin("base.toml"))
+        .add_source(
+            File::from_str("[server]\ndebug = \"yes\"", FileFormat::Toml).origin("local.toml"),
+        )
+        .strict_types(true)
+        .build();
+
+    assert_eq!(
+        res.unwrap_err().to_string(),
+        "conflicting types for key \"server.debug\": boolean from \"base.toml\" and string from \"local.toml\""
+    );
+}
+
+#[test]
+fn test_merge_strict_types_same_type() {
+    let c = Config::builder()
+        .add_source(File::new("tests/Settings", FileFormat::Toml))
+        .add_source(File::new("tests/Settings-production", FileFormat::Toml))
+        .strict_types(true)
+        .build()
+        .unwrap();
+
+    assert_eq!(c.get("debug").ok(), Some(false));
+}
+
+#[test]
+fn test_merge_array_by_key() {
+    let base = r#"
+        [[server]]
+        name = "alpha"
+        host = "alpha.local"
+        port = 8080
+
+        [[server]]
+        name = "beta"
+        host = "beta.local"
+    "#;
+    let local = r#"
+        [[server]]
+        name = "beta"
+        port = 9090
+
+        [[server]]
+        name = "gamma"
+        host = "gamma.local"
+    "#;
+
+    let c = Config::builder()
+        .add_source(File::from_str(base, FileFormat::Toml))
+        .add_source(File::from_str(local, FileFormat::Toml))
+        .array_merge_by_key("server", "name")
+        .unwrap()
+        .build()
+        .unwrap();
+
+    let names: Vec<String> = c
+        .get_array("server")
+        .unwrap()
+        .into_iter()
+        .map(|server| server.into_table().unwrap()["name"].to_string())
+        .collect();
+    assert_eq!(names, vec!["alpha", "beta", "gamma"]);
+
+    assert_eq!(c.get::<i64>("server[0].port").unwrap(), 8080);
+    assert_eq!(c.get::<String>("server[1].host").unwrap(), "beta.local");
+    assert_eq!(c.get::<i64>("server[1].port").unwrap(), 9090);
+    assert_eq!(c.get::<String>("server[2].host").unwrap(), "gamma.local");
+}
+
+#[test]
+fn test_merge_array_by_key_missing_field() {
+    let c = Config::builder()
+        .add_source(File::from_str(
+            "[[server]]\nname = \"alpha\"\n[[server]]\nhost = \"anonymous\"",
+            FileFormat::Toml,
+        ))
+        .add_source(File::from_str(
+            "[[server]]\nhost = \"anonymous\"",
+            FileFormat::Toml,
+        ))
+        .array_merge_by_key("server", "name")
+        .unwrap()
+        .build()
+        .unwrap();
+
+    assert_eq!(c.get_array("server").unwrap().len(), 3);
+    assert_eq!(c.get::<String>("server[2].host").unwrap(), "anonymous");
+}
+
+#[test]
+fn test_merge_array_by_key_duplicates_in_source() {
+    let c = Config::builder()
+        .add_source(File::from_str(
+            "[[server]]\nname = \"alpha\"\nport = 1\n[[server]]\nname = \"alpha\"\nport = 2",
+            FileFormat::Toml,
+        ))
+        .array_merge_by_key("server", "name")
+        .unwrap()
+        .build()
+        .unwrap();
+
+    assert_eq!(c.get_array("server").unwrap().len(), 2);
+    assert_eq!(c.get::<i64>("server[0].port").unwrap(), 1);
+    assert_eq!(c.get::<i64>("server[1].port").unwrap(), 2);
+}
+
+#[test]
+fn test_merge_array_by_key_defaults_and_overrides() {
+    let source = File::from_str(
+        "[[server]]\nname = \"alpha\"\nhost = \"alpha.local\"",
+        FileFormat::Toml,
+    );
+
+    let mut alpha = Map::new();
+    alpha.insert("name".to_string(), Value::from("alpha"));
+    alpha.insert("port".to_string(), Value::from(1));
+
+    let c = Config::builder()
+        .set_default("server", vec![Value::from(alpha)])
+        .unwrap()
+        .add_source(source.clone())
+        .array_merge_by_key("server", "name")
+        .unwrap()
+        .build()
+        .unwrap();
+
+    assert_eq!(c.get_array("server").unwrap().len(), 1);
+    assert_eq!(c.get::<i64>("server[0].port").unwrap(), 1);
+    assert_eq!(c.get::<String>("server[0].host").unwrap(), "alpha.local");
+
+    let mut omega = Map::new();
+    omega.insert("name".to_string(), Value::from("omega"));
+
+    let c = Config::builder()
+        .add_source(source)
+        .set_override("server", vec![Value::from(omega)])
+        .unwrap()
+        .array_merge_by_key("server", "name")
+        .unwrap()
+        .build()
+        .unwrap();
+
+    assert_eq!(c.get_array("server").unwrap().len(), 1);
+    assert_eq!(c.get::<String>("server[0].name").unwrap(), "omega");
+    assert!(!c.contains_key("server[0].host"));
+}
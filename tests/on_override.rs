@@ -0,0 +1,115 @@
+#![cfg(feature = "toml")]
+
+extern crate config;
+
+use std::sync::{Arc, Mutex};
+
+use config::*;
+
+type Calls = Arc<Mutex<Vec<(String, Option<String>, Option<String>, Value, Value)>>>;
+
+fn recorder() -> (Calls, impl Fn(&str, &Value, &Value) + Send + Sync + 'static) {
+    let calls: Calls = Default::default();
+    let hook_calls = calls.clone();
+
+    let hook = move |path: &str, old: &Value, new: &Value| {
+        hook_calls.lock().unwrap().push((
+            path.to_string(),
+            old.origin().map(String::from),
+            new.origin().map(String::from),
+            old.clone(),
+            new.clone(),
+        ));
+    };
+
+    (calls, hook)
+}
+
+#[test]
+fn test_on_override_between_sources() {
+    let (calls, hook) = recorder();
+
+    let c = Config::builder()
+        .add_source(
+            File::from_str(
+                "[database]\nurl = \"file-url\"\npool = 4\n",
+                FileFormat::Toml,
+            )
+            .origin("file"),
+        )
+        .add_source(
+            File::from_str("[database]\nurl = \"env-url\"\n", FileFormat::Toml).origin("env"),
+        )
+        .on_override(hook)
+        .build()
+        .unwrap();
+
+    assert_eq!(c.get::<String>("database.url").unwrap(), "env-url");
+
+    let calls = calls.lock().unwrap();
+    assert_eq!(calls.len(), 1);
+
+    let (path, old_origin, new_origin, old, new) = &calls[0];
+    assert_eq!(path, "database.url");
+    assert_eq!(old_origin.as_deref(), Some("file"));
+    assert_eq!(new_origin.as_deref(), Some("env"));
+    assert_eq!(old.clone().into_string().unwrap(), "file-url");
+    assert_eq!(new.clone().into_string().unwrap(), "env-url");
+}
+
+#[test]
+fn test_on_override_by_override() {
+    let (calls, hook) = recorder();
+
+    Config::builder()
+        .set_default("port", 80)
+        .unwrap()
+        .set_override("port", 8080)
+        .unwrap()
+        .on_override(hook)
+        .build()
+        .unwrap();
+
+    let calls = calls.lock().unwrap();
+    assert_eq!(calls.len(), 1);
+    assert_eq!(calls[0].0, "port");
+    assert_eq!(calls[0].3.clone().into_int().unwrap(), 80);
+    assert_eq!(calls[0].4.clone().into_int().unwrap(), 8080);
+}
+
+#[test]
+fn test_on_override_not_called_without_conflict() {
+    let (calls, hook) = recorder();
+
+    Config::builder()
+        .add_source(File::from_str("a = 1", FileFormat::Toml))
+        .add_source(File::from_str("b = 2", FileFormat::Toml))
+        .on_override(hook)
+        .build()
+        .unwrap();
+
+    assert!(calls.lock().unwrap().is_empty());
+}
+
+#[test]
+fn test_on_override_on_refresh() {
+    let (calls, hook) = recorder();
+
+    let mut c = Config::builder()
+        .set_default("port", 80)
+        .unwrap()
+        .add_named_source("file", File::from_str("port = 8080", FileFormat::Toml))
+        .unwrap()
+        .on_override(hook)
+        .build()
+        .unwrap();
+    calls.lock().unwrap().clear();
+
+    c.refresh_named("file").unwrap();
+
+    let calls = calls.lock().unwrap();
+    assert_eq!(calls.len(), 1);
+    assert_eq!(calls[0].0, "port");
+    assert_eq!(calls[0].3.clone().into_int().unwrap(), 80);
+    assert_eq!(calls[0].4.clone().into_int().unwrap(), 8080);
+}
@@ -0,0 +1,45 @@
+extern crate config;
+
+use config::*;
+
+#[test]
+fn test_check_placeholders_resolved() {
+    std::env::set_var("PLACEHOLDERS_TEST_HOME", "/home/test");
+
+    let builder = Config::builder()
+        .set_default("database.host", "localhost")
+        .unwrap()
+        .set_default("database.url", "postgres://${database.host}/app")
+        .unwrap()
+        .set_default("data_dir", "${PLACEHOLDERS_TEST_HOME}/data")
+        .unwrap()
+        .set_default("literal", "${unterminated")
+        .unwrap();
+
+    assert!(builder.check_placeholders().is_ok());
+}
+
+#[test]
+fn test_check_placeholders_unresolved() {
+    let builder = Config::builder()
+        .set_default("database.host", "localhost")
+        .unwrap()
+        .set_default("database.url", "postgres://${database.hots}/app")
+        .unwrap()
+        .set_default("servers", vec!["${database.host}", "${database.hots}"])
+        .unwrap();
+
+    let err = builder.check_placeholders().unwrap_err();
+
+    assert_eq!(
+        err.to_string(),
+        "unresolved placeholders: ${database.hots}".to_string()
+    );
+
+    // The values are left as they are
+    let config = builder.build().unwrap();
+    assert_eq!(
+        config.get::<String>("database.url").unwrap(),
+        "postgres://${database.hots}/app"
+    );
+}
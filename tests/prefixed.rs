@@ -0,0 +1,59 @@
+#![cfg(feature = "toml")]
+
+extern crate config;
+
+use config::*;
+
+#[test]
+fn test_prefixed_file() {
+    let c = Config::builder()
+        .set_default("database.pool", 4)
+        .unwrap()
+        .add_source(Prefixed::new(
+            File::new("tests/Settings", FileFormat::Toml),
+            "database",
+        ))
+        .build()
+        .unwrap();
+
+    assert_eq!(c.get("database.debug").ok(), Some(true));
+    assert_eq!(
+        c.get("database.place.name").ok(),
+        Some("Torre di Pisa".to_string())
+    );
+    assert_eq!(c.get("database.pool").ok(), Some(4));
+    assert!(!c.contains_key("debug"));
+}
+
+#[test]
+fn test_prefixed_nested_path() {
+    let c = Config::builder()
+        .add_source(Prefixed::new(
+            File::from_str("host = \"localhost\"\nport = 5432", FileFormat::Toml),
+            "services.db",
+        ))
+        .add_source(File::from_str(
+            "[services.db]\nport = 6543",
+            FileFormat::Toml,
+        ))
+        .build()
+        .unwrap();
+
+    assert_eq!(
+        c.get("services.db.host").ok(),
+        Some("localhost".to_string())
+    );
+    assert_eq!(c.get("services.db.port").ok(), Some(6543));
+}
+
+#[test]
+fn test_prefixed_invalid_prefix() {
+    let res = Config::builder()
+        .add_source(Prefixed::new(
+            File::from_str("a = 1", FileFormat::Toml),
+            "not a.[path",
+        ))
+        .build();
+
+    assert_eq!(res.unwrap_err().kind(), ConfigErrorKind::PathParse);
+}
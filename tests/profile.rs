@@ -0,0 +1,68 @@
+#![cfg(feature = "toml")]
+
+extern crate config;
+
+use std::env;
+
+use config::*;
+
+#[test]
+fn test_profile_set_loads_overlay() {
+    env::set_var("PROFILE_TEST_SET", "production");
+
+    let config = Config::builder()
+        .add_profile_file("tests/profile", "PROFILE_TEST_SET", FileFormat::Toml)
+        .build()
+        .unwrap();
+
+    assert_eq!(config.get("debug").ok(), Some(true));
+    assert_eq!(config.get("name").ok(), Some("app".to_string()));
+    assert_eq!(
+        config.get("database.host").ok(),
+        Some("db.example.com".to_string())
+    );
+    assert_eq!(config.get("database.port").ok(), Some(5432));
+
+    env::remove_var("PROFILE_TEST_SET");
+}
+
+#[test]
+fn test_profile_unset_loads_base() {
+    env::remove_var("PROFILE_TEST_UNSET");
+
+    let config = Config::builder()
+        .add_profile_file("tests/profile", "PROFILE_TEST_UNSET", FileFormat::Toml)
+        .build()
+        .unwrap();
+
+    assert_eq!(config.get("debug").ok(), Some(false));
+    assert_eq!(
+        config.get("database.host").ok(),
+        Some("localhost".to_string())
+    );
+}
+
+#[test]
+fn test_profile_missing_overlay_is_optional() {
+    env::set_var("PROFILE_TEST_MISSING", "staging");
+
+    let config = Config::builder()
+        .add_profile_file("tests/profile", "PROFILE_TEST_MISSING", FileFormat::Toml)
+        .build()
+        .unwrap();
+
+    assert_eq!(config.get("debug").ok(), Some(false));
+
+    env::remove_var("PROFILE_TEST_MISSING");
+}
+
+#[test]
+fn test_profile_missing_base() {
+    env::remove_var("PROFILE_TEST_BASE");
+
+    let res = Config::builder()
+        .add_profile_file("tests/missing", "PROFILE_TEST_BASE", FileFormat::Toml)
+        .build();
+
+    assert!(res.is_err());
+}
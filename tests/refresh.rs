@@ -0,0 +1,204 @@
+extern crate config;
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use config::*;
+
+/// Source yielding `key` = number of times it was collected
+#[derive(Debug, Clone)]
+struct Counting {
+    key: &'static str,
+    count: Arc<AtomicUsize>,
+}
+
+impl Counting {
+    fn new(key: &'static str) -> Self {
+        Counting {
+            key,
+            count: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    fn collected(&self) -> usize {
+        self.count.load(Ordering::SeqCst)
+    }
+}
+
+impl Source for Counting {
+    fn clone_into_box(&self) -> Box<dyn Source + Send + Sync> {
+        Box::new((*self).clone())
+    }
+
+    fn collect(&self) -> Result<Map<String, Value>, ConfigError> {
+        let count = self.count.fetch_add(1, Ordering::SeqCst) + 1;
+
+        let mut m = Map::new();
+        m.insert(self.key.to_string(), (count as i64).into());
+        m.insert("shared".to_string(), self.key.into());
+        Ok(m)
+    }
+}
+
+#[test]
+fn test_refresh_named() {
+    let local = Counting::new("local");
+    let remote = Counting::new("remote");
+
+    let mut config = Config::builder()
+        .set_default("default", true)
+        .unwrap()
+        .add_named_source("local", local.clone())
+        .unwrap()
+        .add_named_source("remote", remote.clone())
+        .unwrap()
+        .build()
+        .unwrap();
+
+    assert_eq!(config.get::<i64>("remote").unwrap(), 1);
+
+    config.refresh_named("remote").unwrap();
+
+    assert_eq!(local.collected(), 1);
+    assert_eq!(remote.collected(), 2);
+    assert_eq!(config.get::<i64>("local").unwrap(), 1);
+    assert_eq!(config.get::<i64>("remote").unwrap(), 2);
+    assert_eq!(config.get::<String>("shared").unwrap(), "remote");
+    assert!(config.get::<bool>("default").unwrap());
+}
+
+#[test]
+fn test_refresh_named_keeps_precedence() {
+    let first = Counting::new("first");
+    let second = Counting::new("second");
+
+    let mut config = Config::builder()
+        .add_named_source("first", first.clone())
+        .unwrap()
+        .add_named_source("second", second)
+        .unwrap()
+        .set_override("shared", "override")
+        .unwrap()
+        .build()
+        .unwrap();
+
+    config.refresh_named("first").unwrap();
+
+    assert_eq!(first.collected(), 2);
+    assert_eq!(config.get::<String>("shared").unwrap(), "override");
+}
+
+#[test]
+fn test_refresh_named_unknown() {
+    let mut config = Config::builder()
+        .add_named_source("local", Counting::new("local"))
+        .unwrap()
+        .build()
+        .unwrap();
+
+    assert_eq!(
+        config.refresh_named("remote").unwrap_err().to_string(),
+        "no source named \"remote\""
+    );
+}
+
+#[test]
+fn test_add_named_source_duplicate() {
+    let res = Config::builder()
+        .add_named_source("local", Counting::new("a"))
+        .unwrap()
+        .add_named_source("local", Counting::new("b"));
+
+    assert_eq!(
+        res.unwrap_err().to_string(),
+        "a source named \"local\" is already registered"
+    );
+}
+
+#[test]
+fn test_set_default_layer() {
+    let source = Counting::new("source");
+
+    let mut config = Config::builder()
+        .set_default("level", "info")
+        .unwrap()
+        .set_default("timeout", 30)
+        .unwrap()
+        .set_default("shared", "default")
+        .unwrap()
+        .add_source(source.clone())
+        .set_override("timeout", 5)
+        .unwrap()
+        .build()
+        .unwrap();
+
+    let mut defaults = Map::new();
+    defaults.insert("level".to_string(), "debug".into());
+    defaults.insert("timeout".to_string(), 60.into());
+    defaults.insert("shared".to_string(), "default".into());
+    defaults.insert("db.pool".to_string(), 4.into());
+    config.set_default_layer(defaults).unwrap();
+
+    assert_eq!(source.collected(), 1);
+    assert_eq!(config.get::<String>("level").unwrap(), "debug");
+    assert_eq!(config.get::<i64>("db.pool").unwrap(), 4);
+    assert_eq!(config.get::<i64>("timeout").unwrap(), 5);
+    assert_eq!(config.get::<String>("shared").unwrap(), "source");
+}
+
+#[test]
+fn test_set_override_layer() {
+    let mut config = Config::builder()
+        .set_default("level", "info")
+        .unwrap()
+        .add_source(Counting::new("source"))
+        .set_override("timeout", 5)
+        .unwrap()
+        .build()
+        .unwrap();
+
+    let mut overrides = Map::new();
+    overrides.insert("shared".to_string(), "override".into());
+    config.set_override_layer(overrides).unwrap();
+
+    assert_eq!(config.get::<String>("shared").unwrap(), "override");
+    assert_eq!(config.get::<String>("level").unwrap(), "info");
+    assert!(!config.contains_key("timeout"));
+
+    let mut invalid = Map::new();
+    invalid.insert("a[".to_string(), 1.into());
+    assert!(config.set_override_layer(invalid).is_err());
+    assert_eq!(config.get::<String>("shared").unwrap(), "override");
+}
+
+#[test]
+#[allow(deprecated)]
+fn test_set_keeps_builder_options() {
+    let source = Counting::new("source");
+
+    let mut config = Config::builder()
+        .set_default("level", "info")
+        .unwrap()
+        .add_named_source("source", source.clone())
+        .unwrap()
+        .map_transform(|mut map| {
+            map.insert("transformed".into(), true.into());
+            Ok(map)
+        })
+        .build()
+        .unwrap();
+
+    config.set("level", "debug").unwrap();
+
+    assert_eq!(source.collected(), 2);
+    assert_eq!(config.get::<i64>("source").unwrap(), 2);
+    assert!(config.get::<bool>("transformed").unwrap());
+    assert!(!config.is_default("level"));
+
+    // A later refresh works from the layers collected by `set`
+    config.refresh_named("source").unwrap();
+
+    assert_eq!(config.get::<i64>("source").unwrap(), 3);
+    assert_eq!(config.get::<String>("level").unwrap(), "debug");
+    assert!(config.get::<bool>("transformed").unwrap());
+}
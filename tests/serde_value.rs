@@ -0,0 +1,61 @@
+#![cfg(all(feature = "json", feature = "toml"))]
+
+extern crate config;
+extern crate serde_json;
+extern crate toml;
+
+use config::*;
+
+#[test]
+fn test_json_value() {
+    let value = serde_json::json!({
+        "debug": true,
+        "port": 8080,
+        "ratio": 0.5,
+        "name": "svc",
+        "missing": null,
+        "tags": ["a", "b"],
+        "database": { "url": "postgres://localhost", "pool": { "size": 4 } }
+    });
+
+    let m = SerdeValue::new(value).origin("memory").collect().unwrap();
+
+    assert_eq!(m["debug"].kind, ValueKind::Boolean(true));
+    assert_eq!(m["port"].kind, ValueKind::Integer(8080));
+    assert_eq!(m["ratio"].kind, ValueKind::Float(0.5));
+    assert_eq!(m["name"].kind, ValueKind::String("svc".into()));
+    assert_eq!(m["missing"].kind, ValueKind::Nil);
+    assert_eq!(m["tags"].clone().into_array().unwrap().len(), 2);
+    assert_eq!(m["name"].origin(), Some("memory"));
+
+    let c = Config::builder()
+        .add_source(source::SerdeValue::new(serde_json::json!({
+            "database": { "pool": { "size": 4 } }
+        })))
+        .build()
+        .unwrap();
+
+    assert_eq!(c.get::<i64>("database.pool.size").unwrap(), 4);
+}
+
+#[test]
+fn test_json_value_not_object() {
+    let res = SerdeValue::new(serde_json::json!([1, 2])).collect();
+
+    assert_eq!(
+        res.unwrap_err().to_string(),
+        "invalid type: sequence, expected a map"
+    );
+}
+
+#[test]
+fn test_toml_value() {
+    let value: toml::Value = toml::from_str("[server]\nport = 80\n").unwrap();
+
+    let c = Config::builder()
+        .add_source(SerdeValue::new(value))
+        .build()
+        .unwrap();
+
+    assert_eq!(c.get::<i64>("server.port").unwrap(), 80);
+}
@@ -91,3 +91,19 @@ fn test_set_capital() {
     assert_eq!(config.get("ThAt").ok(), Some(true));
     assert_eq!(config.get("logLevel").ok(), Some(5));
 }
+
+#[test]
+fn test_key_separator() {
+    let config = Config::builder()
+        .key_separator('/')
+        .set_override("sites/a.com/port", 8080)
+        .unwrap()
+        .set_override("sites/b.org/ports[0]", 80)
+        .unwrap()
+        .build()
+        .unwrap();
+
+    assert_eq!(config.get("sites/a.com/port").ok(), Some(8080));
+    assert_eq!(config.get("sites/b.org/ports[0]").ok(), Some(80));
+    assert!(config.contains_key("sites/a.com"));
+}
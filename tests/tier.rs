@@ -0,0 +1,59 @@
+#![cfg(feature = "toml")]
+
+extern crate config;
+
+use config::*;
+
+fn env() -> Environment {
+    let mut vars = Map::new();
+    vars.insert("APP_PORT".to_string(), "9090".to_string());
+
+    Environment::with_prefix("APP")
+        .try_parsing(true)
+        .source(Some(vars))
+}
+
+fn file() -> File<FileSourceString> {
+    File::from_str("port = 8080\nhost = \"localhost\"", FileFormat::Toml)
+}
+
+#[test]
+fn test_tier_env_beats_later_file() {
+    let config = Config::builder()
+        .add_source_to_tier(env(), Tier::Env)
+        .add_source_to_tier(file(), Tier::File)
+        .build()
+        .unwrap();
+
+    assert_eq!(config.get::<i64>("port").unwrap(), 9090);
+    assert_eq!(config.get::<String>("host").unwrap(), "localhost");
+
+    // Sources added without a tier are files too
+    let config = Config::builder()
+        .add_source_to_tier(env(), Tier::Env)
+        .add_source(file())
+        .build()
+        .unwrap();
+
+    assert_eq!(config.get::<i64>("port").unwrap(), 9090);
+}
+
+#[test]
+fn test_tier_insertion_order() {
+    let config = Config::builder()
+        .add_source(env())
+        .add_source(file())
+        .build()
+        .unwrap();
+
+    assert_eq!(config.get::<i64>("port").unwrap(), 8080);
+
+    let config = Config::builder()
+        .add_source_to_tier(File::from_str("port = 1", FileFormat::Toml), Tier::Override)
+        .add_source_to_tier(env(), Tier::Env)
+        .add_source(file())
+        .build()
+        .unwrap();
+
+    assert_eq!(config.get::<i64>("port").unwrap(), 1);
+}
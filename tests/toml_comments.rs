@@ -0,0 +1,74 @@
+#![cfg(feature = "toml_comments")]
+
+extern crate config;
+
+use config::*;
+
+const SETTINGS: &str = r#"
+# Settings of the service
+
+# Port the server listens on
+port = 8080
+debug = false
+
+# Primary database
+#
+# Used for everything but analytics
+[database]
+url = "postgres://localhost" # not a leading comment
+
+  # Connections kept open
+pool-size = 4
+
+# First replica
+[[replicas]]
+host = "a"
+"#;
+
+#[test]
+fn test_comments() {
+    let c = Config::builder()
+        .add_source(File::from_str(SETTINGS, FileFormat::Toml).with_comments(true))
+        .build()
+        .unwrap();
+
+    assert_eq!(c.comment("port"), Some("Port the server listens on"));
+    assert_eq!(
+        c.comment("database"),
+        Some("Primary database\n\nUsed for everything but analytics")
+    );
+    assert_eq!(
+        c.comment("database.pool-size"),
+        Some("Connections kept open")
+    );
+    assert_eq!(c.comment("replicas[0]"), Some("First replica"));
+    assert_eq!(c.comment("debug"), None);
+    assert_eq!(c.comment("database.url"), None);
+    assert_eq!(c.comment("missing"), None);
+}
+
+#[test]
+fn test_comments_disabled() {
+    let c = Config::builder()
+        .add_source(File::from_str(SETTINGS, FileFormat::Toml))
+        .build()
+        .unwrap();
+
+    assert_eq!(c.comment("port"), None);
+}
+
+#[test]
+fn test_comments_root_key_and_kebab_to_snake() {
+    let c = Config::builder()
+        .add_source(
+            File::from_str(SETTINGS, FileFormat::Toml)
+                .with_comments(true)
+                .root_key("database")
+                .kebab_to_snake(true),
+        )
+        .build()
+        .unwrap();
+
+    assert_eq!(c.comment("pool_size"), Some("Connections kept open"));
+    assert_eq!(c.comment("port"), None);
+}
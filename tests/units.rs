@@ -0,0 +1,96 @@
+#![cfg(feature = "toml")]
+
+extern crate config;
+extern crate serde;
+
+#[macro_use]
+extern crate serde_derive;
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use config::*;
+
+#[derive(Debug, Deserialize)]
+struct Limits {
+    upload: ByteSize,
+    cache: Option<ByteSize>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Settings {
+    timeouts: Vec<HumanDuration>,
+    limits: Limits,
+    retries: HashMap<String, HumanDuration>,
+}
+
+fn make() -> Config {
+    Config::builder()
+        .add_source(File::from_str(
+            r#"
+            timeouts = ["1s", "500ms", 2]
+
+            [limits]
+            upload = "10MB"
+            cache = 4096
+
+            [retries]
+            connect = "1.5s"
+            read = "1m"
+            "#,
+            FileFormat::Toml,
+        ))
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn test_durations_in_array() {
+    let timeouts: Vec<HumanDuration> = make().get("timeouts").unwrap();
+
+    assert_eq!(
+        timeouts,
+        vec![
+            HumanDuration(Duration::from_secs(1)),
+            HumanDuration(Duration::from_millis(500)),
+            HumanDuration(Duration::from_secs(2)),
+        ]
+    );
+}
+
+#[test]
+fn test_units_in_nested_struct() {
+    let settings: Settings = make().try_into().unwrap();
+
+    assert_eq!(settings.timeouts.len(), 3);
+    assert_eq!(settings.limits.upload, ByteSize(10_000_000));
+    assert_eq!(settings.limits.cache, Some(ByteSize(4096)));
+    assert_eq!(
+        Duration::from(settings.retries["connect"]),
+        Duration::from_millis(1500)
+    );
+    assert_eq!(settings.retries["read"].0, Duration::from_secs(60));
+}
+
+#[test]
+fn test_durations_in_map() {
+    let retries: HashMap<String, HumanDuration> = make().get("retries").unwrap();
+
+    assert_eq!(retries.len(), 2);
+    assert_eq!(retries["connect"].0, Duration::from_millis(1500));
+}
+
+#[test]
+fn test_invalid_unit() {
+    let c = Config::builder()
+        .set_default("timeouts", vec!["1s", "1 fortnight"])
+        .unwrap()
+        .build()
+        .unwrap();
+
+    let err = c.get::<Vec<HumanDuration>>("timeouts").unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "invalid type: string \"1 fortnight\", expected a duration such as 30 or \"30s\""
+    );
+}
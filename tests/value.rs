@@ -0,0 +1,48 @@
+extern crate config;
+
+use std::collections::HashMap;
+
+use config::*;
+
+#[test]
+fn test_from_scalars() {
+    assert_eq!(Value::from(42i64).kind, ValueKind::Integer(42));
+    assert_eq!(Value::from(0.5).kind, ValueKind::Float(0.5));
+    assert_eq!(Value::from(true).kind, ValueKind::Boolean(true));
+    assert_eq!(Value::from("str").kind, ValueKind::String("str".into()));
+    assert_eq!(Value::from(None::<i64>).kind, ValueKind::Nil);
+    assert_eq!(Value::from("str").origin(), None);
+}
+
+#[test]
+fn test_from_nested() {
+    let mut pool = HashMap::new();
+    pool.insert("size".to_string(), Value::from(4i64));
+    pool.insert("hosts".to_string(), Value::from(vec!["a", "b"]));
+
+    let mut database = HashMap::new();
+    database.insert("url".to_string(), Value::from("postgres://localhost"));
+    database.insert("pool".to_string(), Value::from(pool));
+
+    let mut root = HashMap::new();
+    root.insert("database".to_string(), Value::from(database));
+
+    let value = Value::from(root);
+    assert!(matches!(value.kind, ValueKind::Table(_)));
+
+    let c = Config::builder()
+        .set_default("root", value)
+        .unwrap()
+        .build()
+        .unwrap();
+
+    assert_eq!(c.get::<i64>("root.database.pool.size").unwrap(), 4);
+    assert_eq!(
+        c.get::<Vec<String>>("root.database.pool.hosts").unwrap(),
+        vec!["a", "b"]
+    );
+    assert_eq!(
+        c.get::<String>("root.database.url").unwrap(),
+        "postgres://localhost"
+    );
+}